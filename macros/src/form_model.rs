@@ -0,0 +1,217 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, Error, Lit, Meta, NestedMeta, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// `FormDataField` implementation of the field type
+    Field,
+    /// `#[form_model(object)]` - borsh object via `add_object()`
+    Object,
+    /// `#[form_model(as_str)]` - enum (`EnumTrait`) stored as a string
+    Enum,
+    /// `#[form_model(id)]` - `FormData::id`
+    Id,
+    /// `#[form_model(skip)]` - not stored, `Default::default()` on load
+    Skip,
+}
+
+struct FieldModel {
+    ident: syn::Ident,
+    ty: Type,
+    key: String,
+    mode: Mode,
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    if let Type::Path(type_path) = ty {
+        let segment = type_path.path.segments.last()?;
+        if segment.ident == "Option" {
+            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+                    return Some(ty);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn field_model(field: &syn::Field) -> syn::Result<FieldModel> {
+    let ident = field.ident.clone().unwrap();
+    let mut key = ident.to_string();
+    let mut mode = Mode::Field;
+
+    for attr in field.attrs.iter() {
+        if !attr.path.is_ident("form_model") {
+            continue;
+        }
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "usage: #[form_model(rename = \"key\", object | as_str | id | skip)]",
+                ))
+            }
+        };
+        for nested in list.nested.iter() {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("object") => {
+                    mode = Mode::Object
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("as_str") => mode = Mode::Enum,
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("id") => mode = Mode::Id,
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => mode = Mode::Skip,
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    match &nv.lit {
+                        Lit::Str(lit) => key = lit.value(),
+                        lit => return Err(Error::new_spanned(lit, "rename expects a string")),
+                    }
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        nested,
+                        "unsupported #[form_model] attribute, supported attributes are rename, object, as_str, id, skip",
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(FieldModel {
+        ident,
+        ty: field.ty.clone(),
+        key,
+        mode,
+    })
+}
+
+impl FieldModel {
+    fn add(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let key = &self.key;
+        let optional = option_inner(&self.ty).is_some();
+        let value = if optional {
+            quote! { value }
+        } else {
+            quote! { model.#ident }
+        };
+
+        let add = match self.mode {
+            Mode::Field => {
+                return quote! {
+                    workflow_ux::form::FormDataField::add_to(model.#ident, &mut data, #key)?;
+                }
+            }
+            Mode::Id => return quote! { data.id = model.#ident; },
+            Mode::Skip => return quote! {},
+            Mode::Object => quote! { data.add_object(#key, #value)?; },
+            Mode::Enum => quote! { data.add_enum(#key, #value); },
+        };
+
+        if optional {
+            quote! {
+                if let Some(value) = model.#ident {
+                    #add
+                }
+            }
+        } else {
+            add
+        }
+    }
+
+    fn get(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let ty = &self.ty;
+        let key = &self.key;
+        let optional = option_inner(ty).is_some();
+        let value = match self.mode {
+            Mode::Field => {
+                return quote! {
+                    #ident: <#ty as workflow_ux::form::FormDataField>::get_from(&data, #key)?
+                }
+            }
+            Mode::Id => return quote! { #ident: data.id.clone() },
+            Mode::Skip => return quote! { #ident: Default::default() },
+            Mode::Object => quote! { data.get_object(#key)? },
+            Mode::Enum => quote! { data.get_enum(#key)? },
+        };
+
+        if optional {
+            quote! { #ident: #value }
+        } else {
+            quote! {
+                #ident: #value.ok_or_else(|| workflow_ux::error::Error::FormDataMissingField(#key.to_string()))?
+            }
+        }
+    }
+}
+
+pub fn derive_form_model(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let struct_name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let fields = if let syn::Data::Struct(syn::DataStruct {
+        fields: syn::Fields::Named(ref fields),
+        ..
+    }) = ast.data
+    {
+        fields
+    } else {
+        return Error::new_spanned(
+            struct_name,
+            "#[derive(FormModel)] supports only structs with named fields",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let mut models = Vec::new();
+    for field in fields.named.iter() {
+        match field_model(field) {
+            Ok(model) => models.push(model),
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+
+    let adds: Vec<TokenStream2> = models.iter().map(|model| model.add()).collect();
+    let gets: Vec<TokenStream2> = models.iter().map(|model| model.get()).collect();
+
+    quote! {
+        impl #impl_generics ::core::convert::TryFrom<#struct_name #ty_generics> for workflow_ux::form::FormData #where_clause {
+            type Error = workflow_ux::error::Error;
+
+            fn try_from(model: #struct_name #ty_generics) -> workflow_ux::result::Result<Self> {
+                let mut data = workflow_ux::form::FormData::new(None);
+                #( #adds )*
+                Ok(data)
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<workflow_ux::form::FormData> for #struct_name #ty_generics #where_clause {
+            type Error = workflow_ux::error::Error;
+
+            fn try_from(data: workflow_ux::form::FormData) -> workflow_ux::result::Result<Self> {
+                Ok(Self {
+                    #( #gets ),*
+                })
+            }
+        }
+
+        impl #impl_generics workflow_ux::form::FormDataField for #struct_name #ty_generics #where_clause {
+            fn add_to(self, data: &mut workflow_ux::form::FormData, name: &str) -> workflow_ux::result::Result<()> {
+                let model = <workflow_ux::form::FormData as ::core::convert::TryFrom<Self>>::try_from(self)?;
+                workflow_ux::form::FormDataField::add_to(model, data, name)
+            }
+
+            fn get_from(data: &workflow_ux::form::FormData, name: &str) -> workflow_ux::result::Result<Self> {
+                let data = <workflow_ux::form::FormData as workflow_ux::form::FormDataField>::get_from(data, name)?;
+                <Self as ::core::convert::TryFrom<workflow_ux::form::FormData>>::try_from(data)
+            }
+        }
+    }
+    .into()
+}
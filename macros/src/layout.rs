@@ -103,6 +103,323 @@ fn get_type_info(field_type: &syn::Type) -> (Option<syn::Path>, Option<syn::Path
 //     let attributes: FieldAttributes = attr.parse_args().unwrap();
 //     Ok(attributes)
 // }
+
+/// Controls implementing `workflow_ux::form::FormValue`, populated
+/// from the `FormData` key matching the field name.
+const LOADABLE_CONTROLS: &[&str] = &[
+    "input", "textarea", "mnemonic", "select", "radio", "checkbox", "repeater",
+];
+
+/// Controls of the crate; any other field type declared with `#[layout]`
+/// is a nested layout struct.
+const CONTROLS: &[&str] = &[
+    "action", "avatar", "badge", "baseelement", "checkbox", "date", "duration", "hiddenid",
+    "html", "input", "markdown", "md", "mnemonic", "multiselect", "qrcode", "radio",
+    "radiobtns", "repeater", "select", "selector", "stagefooter", "svg", "terminal", "text",
+    "textarea", "tokenselect", "tokenselector",
+];
+
+/// Nested layout fields are stored as a JSON object of their values
+/// (or, within a `#[stage]` layout, alongside the values of the other stages).
+fn is_nested_layout(field: &Field) -> bool {
+    field.args.contains_key("layout") && !CONTROLS.contains(&field.type_name_str_lower_case.as_str())
+}
+
+/// Translates the validation attributes of a control field into
+/// `workflow_ux::validation::Rule` constructors.
+fn validation_rules(field_name: &Ident, args: &Args) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let kv: HashMap<String, String> = args.to_string_kv().into_iter().collect();
+    let mut rules = Vec::new();
+
+    let flag = |name: &str| matches!(kv.get(name), Some(v) if v != "false");
+    let number = |name: &str| -> syn::Result<Option<f64>> {
+        match kv.get(name) {
+            Some(v) => v.parse::<f64>().map(Some).map_err(|_| {
+                Error::new_spanned(
+                    field_name,
+                    format!("#[field]: `{name}` expects a number, got `{v}`"),
+                )
+            }),
+            None => Ok(None),
+        }
+    };
+    let length = |name: &str| -> syn::Result<Option<usize>> {
+        match kv.get(name) {
+            Some(v) => v.parse::<usize>().map(Some).map_err(|_| {
+                Error::new_spanned(
+                    field_name,
+                    format!("#[field]: `{name}` expects a length, got `{v}`"),
+                )
+            }),
+            None => Ok(None),
+        }
+    };
+
+    if flag("required") {
+        rules.push(quote! { workflow_ux::validation::Rule::Required });
+    }
+    if let Some(len) = length("min_len")? {
+        rules.push(quote! { workflow_ux::validation::Rule::MinLen(#len) });
+    }
+    if let Some(len) = length("max_len")? {
+        rules.push(quote! { workflow_ux::validation::Rule::MaxLen(#len) });
+    }
+    if let Some(pattern) = kv.get("pattern") {
+        if let Err(err) = regex::Regex::new(pattern) {
+            return Err(Error::new_spanned(
+                field_name,
+                format!("#[field]: invalid `pattern` `{pattern}`: {err}"),
+            ));
+        }
+        rules.push(quote! {
+            workflow_ux::validation::Rule::Pattern({
+                static PATTERN: workflow_ux::validation::Pattern =
+                    workflow_ux::validation::Pattern::new(#pattern);
+                &PATTERN
+            })
+        });
+    }
+    if let Some(min) = number("min")? {
+        rules.push(quote! { workflow_ux::validation::Rule::Min(#min) });
+    }
+    if let Some(max) = number("max")? {
+        rules.push(quote! { workflow_ux::validation::Rule::Max(#max) });
+    }
+    if flag("email") {
+        rules.push(quote! { workflow_ux::validation::Rule::Email });
+    }
+    if let Some(custom) = kv.get("custom") {
+        let custom: syn::Path = syn::parse_str(custom).map_err(|_| {
+            Error::new_spanned(
+                field_name,
+                format!("#[field]: `custom` expects a function path, got `{custom}`"),
+            )
+        })?;
+        rules.push(quote! { workflow_ux::validation::Rule::Custom(#custom) });
+    }
+
+    Ok(rules)
+}
+
+/// First generic argument of a field type, i.e. `E` of `Select<E>`
+fn generic_argument(ty: &Type) -> Option<&Type> {
+    if let Type::Path(type_path) = ty {
+        if let PathArguments::AngleBracketed(args) = &type_path.path.segments.last()?.arguments {
+            if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+                return Some(ty);
+            }
+        }
+    }
+    None
+}
+
+/// `workflow_ux::schema::FieldSchema` of a field, `None` for fields
+/// not stored in the `FormData` of the layout (text, buttons etc.)
+fn field_schema(
+    field: &Field,
+    ctl_args: &Args,
+    layout_args: &Args,
+    rules: &[proc_macro2::TokenStream],
+) -> Option<proc_macro2::TokenStream> {
+    let kv: HashMap<String, String> = ctl_args.to_string_kv().into_iter().collect();
+    let ty = &field.type_name;
+    let kind = if is_nested_layout(field) {
+        quote! { workflow_ux::schema::FieldSchema::object(<#ty>::schema()) }
+    } else {
+        match field.type_name_str_lower_case.as_str() {
+            // input values are kept as text
+            "input" => match kv.get("type").map(|t| t.as_str()) {
+                Some("number") | Some("range") => quote! { workflow_ux::schema::FieldSchema::numeric() },
+                Some("password") => quote! { workflow_ux::schema::FieldSchema::string().with("writeOnly", true) },
+                Some("email") => quote! { workflow_ux::schema::FieldSchema::string().with("format", "email") },
+                _ => quote! { workflow_ux::schema::FieldSchema::string() },
+            },
+            "textarea" => quote! { workflow_ux::schema::FieldSchema::string() },
+            "mnemonic" => quote! { workflow_ux::schema::FieldSchema::string().with("writeOnly", true) },
+            "checkbox" => quote! { workflow_ux::schema::FieldSchema::boolean() },
+            "select" | "radio" => {
+                let e = generic_argument(ty)?;
+                quote! { workflow_ux::schema::FieldSchema::enumeration::<#e>() }
+            }
+            "repeater" => {
+                let item = generic_argument(ty)?;
+                let mut kind = quote! {
+                    workflow_ux::schema::FieldSchema::array(workflow_ux::schema::FieldSchema::object(<#item>::schema()))
+                };
+                for (attr, keyword) in [("min_items", "minItems"), ("max_items", "maxItems")] {
+                    if let Some(Ok(count)) = kv.get(attr).map(|v| v.parse::<usize>()) {
+                        kind = quote! { #kind.with(#keyword, #count) };
+                    }
+                }
+                kind
+            }
+            _ => return None,
+        }
+    };
+
+    let layout_kv: HashMap<String, String> = layout_args.to_string_kv().into_iter().collect();
+    let title = match kv.get("label").or_else(|| layout_kv.get("title")) {
+        Some(title) => quote! { .with_title(#title) },
+        None => quote! {},
+    };
+    let mut extensions = quote! {};
+    for (attr, keyword) in [("show_if", "x-show-if"), ("enable_if", "x-enable-if")] {
+        if let Some(expr) = kv.get(attr) {
+            extensions = quote! { #extensions.with(#keyword, #expr) };
+        }
+    }
+    let docs = &field.docs;
+    Some(quote! {
+        #kind
+            #title
+            .with_docs(&[#( #docs ),*])
+            .with_rules(&[#( #rules ),*])
+            #extensions
+    })
+}
+
+/// Parses a `show_if`/`enable_if` expression (`field == value`, `field != value`,
+/// `field` or `!field`) into the source field and a `workflow_ux::condition::Condition`.
+fn field_condition(
+    field_name: &Ident,
+    attr: &str,
+    expr: &str,
+) -> syn::Result<(Ident, proc_macro2::TokenStream)> {
+    let unquote = |v: &str| v.trim().trim_matches(|c| c == '"' || c == '\'').to_string();
+    let (source, condition) = if let Some((source, value)) = expr.split_once("!=") {
+        let value = unquote(value);
+        (source, quote! { workflow_ux::condition::Condition::Ne(#value.to_string()) })
+    } else if let Some((source, value)) = expr.split_once("==") {
+        let value = unquote(value);
+        (source, quote! { workflow_ux::condition::Condition::Eq(#value.to_string()) })
+    } else if let Some(source) = expr.trim().strip_prefix('!') {
+        (source, quote! { workflow_ux::condition::Condition::Falsy })
+    } else {
+        (expr, quote! { workflow_ux::condition::Condition::Truthy })
+    };
+    let source: Ident = syn::parse_str(source.trim()).map_err(|_| {
+        Error::new_spanned(
+            field_name,
+            format!("#[field]: `{attr}` expects `field == value`, `field != value`, `field` or `!field`, got `{expr}`"),
+        )
+    })?;
+    Ok((source, condition))
+}
+
+/// Stage attributes declared on a nested layout field of a `#[stage]` layout
+/// (`#[layout(active = path, next = path)]`), evaluated over the data of the
+/// visited stages: `active(&FormData) -> bool` decides whether the stage is
+/// on the path and `next(&FormData) -> Option<&'static str>` names the stage
+/// following it.
+const STAGE_ATTRIBUTES: [&str; 2] = ["active", "next"];
+
+fn stage_predicates(
+    field: &Field,
+) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let no_args = Args::new();
+    let pane_args = field.args.get(&String::from("pane")).unwrap_or(&no_args);
+    let layout_args = field.args.get(&String::from("layout")).unwrap_or(pane_args);
+    let kv: HashMap<String, String> = layout_args.to_string_kv().into_iter().collect();
+    let path = |name: &str| -> syn::Result<Option<syn::Path>> {
+        match kv.get(name) {
+            Some(v) => syn::parse_str::<syn::Path>(v).map(Some).map_err(|_| {
+                Error::new_spanned(
+                    &field.field_name,
+                    format!("#[layout]: `{name}` expects a function path, got `{v}`"),
+                )
+            }),
+            None => Ok(None),
+        }
+    };
+    let active = match path("active")? {
+        Some(active) => quote! { #active(data) },
+        None => quote! { true },
+    };
+    let next = match path("next")? {
+        Some(next) => quote! { #next(data) },
+        None => quote! { None },
+    };
+    Ok((active, next))
+}
+
+/// Step of a stage field of a `#[stage]` layout shown by the stepper
+/// (`#[stage(title = "...", icon = "...")]`).  Returns the `Option<String>`
+/// title and `Option<Icon>` icon expressions, `None` without the attribute.
+const STAGE_STEP_ATTRIBUTES: [&str; 2] = ["title", "icon"];
+
+fn stage_step(
+    field: &Field,
+) -> syn::Result<Option<(proc_macro2::TokenStream, proc_macro2::TokenStream)>> {
+    let args = match field.args.get(&String::from("stage")) {
+        Some(args) => args,
+        None => return Ok(None),
+    };
+    let kv: HashMap<String, String> = args.to_string_kv().into_iter().collect();
+    if let Some(name) = kv.keys().find(|k| !STAGE_STEP_ATTRIBUTES.contains(&k.as_str())) {
+        return Err(Error::new_spanned(
+            &field.field_name,
+            format!("#[stage]: unsupported attribute `{name}`, expected `title` or `icon`"),
+        ));
+    }
+    let title = match kv.get("title") {
+        Some(title) => quote! { Some(workflow_ux::prelude::i18n(#title)) },
+        None => quote! { None },
+    };
+    let icon = match kv.get("icon") {
+        Some(icon) => quote! { Some(workflow_ux::icon::Icon::css(#icon)) },
+        None => quote! { None },
+    };
+    Ok(Some((title, icon)))
+}
+
+/// Generates the `workflow_ux::validation::AsyncValidator` binding
+/// for a control field declaring `async_validator = fn`.
+fn async_validator(
+    field_name: &Ident,
+    args: &Args,
+    rules: &[proc_macro2::TokenStream],
+) -> Option<syn::Result<proc_macro2::TokenStream>> {
+    let kv: HashMap<String, String> = args.to_string_kv().into_iter().collect();
+    let validator = kv.get("async_validator")?;
+    let validator: syn::Path = match syn::parse_str(validator) {
+        Ok(validator) => validator,
+        Err(_) => {
+            return Some(Err(Error::new_spanned(
+                field_name,
+                format!("#[field]: `async_validator` expects a function path, got `{validator}`"),
+            )))
+        }
+    };
+    let delay = match kv.get("debounce").map(|v| v.parse::<u32>()) {
+        Some(Ok(delay)) => delay,
+        Some(Err(_)) => {
+            return Some(Err(Error::new_spanned(
+                field_name,
+                "#[field]: `debounce` expects a delay in milliseconds",
+            )))
+        }
+        None => 500,
+    };
+
+    // kept in a local, so that `show_if` conditions can deactivate it
+    let binding = async_validator_ident(field_name);
+    Some(Ok(quote! {
+        let #binding = workflow_ux::validation::AsyncValidator::bind(
+            &#field_name,
+            vec![#( #rules ),*],
+            #delay,
+            std::sync::Arc::new(|value: String| -> workflow_ux::validation::AsyncValidatorFuture {
+                Box::pin(#validator(value))
+            }),
+        )?;
+        _validators.push(#binding.clone())?;
+    }))
+}
+
+fn async_validator_ident(field_name: &Ident) -> Ident {
+    Ident::new(&format!("_{field_name}_async_validator"), Span::call_site())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Layout {
     Form,
@@ -201,6 +518,7 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
 //                attr.path.is_ident("option") ||   // should be processed before and retain types
                 attr.path.is_ident("section") || 
                 attr.path.is_ident("pane") || 
+                attr.path.is_ident("stage") || 
                 attr.path.is_ident(&type_name_str_lower_case)
             })
             .collect();
@@ -337,6 +655,12 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
     }
     // println!("******************************** FIELD DONE");
     let mut field_initializers = Vec::new();
+    let mut field_validators = Vec::new();
+    let mut async_validators = Vec::new();
+    let mut field_loaders = Vec::new();
+    let mut field_collectors = Vec::new();
+    let mut field_conditions = Vec::new();
+    let mut field_schemas = Vec::new();
     for field in fields.iter() {
         // println!("******************************** FIELD A {:?}",field);
         // println!("******************************** FIELD A");
@@ -377,10 +701,173 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
         // let pane_args = field.args.get(&String::from("pane")).unwrap_or(&no_args);
         // let layout_args = field.args.get(&String::from("section")).unwrap_or(&pane_args);
 
-        let ctl_attrs_kv = ctl_args.to_string_kv();
+        let skip = match ctl_args.get("skip") {
+            Some(Some(skip)) => skip.to_token_stream().to_string().replace('"', "") == "true",
+            _ => false,
+        };
+        // fields are registered with their control (see `ElementLayout::insert_at()`),
+        // which requires `'static` controls
+        let registered = layout != Layout::Html && !skip && struct_params.params.is_empty();
+        let field_key = field_name.to_string();
+        // the generated functions use the control registered under the field key,
+        // skipping fields removed from the layout or replaced with another type
+        let with_control = |tokens: proc_macro2::TokenStream| {
+            if registered {
+                quote! {
+                    if let Some(control) = self._layout.get::<#type_name>(#field_key).as_ref() {
+                        #tokens
+                    }
+                }
+            } else {
+                quote! {
+                    {
+                        let control = &self.#field_name;
+                        #tokens
+                    }
+                }
+            }
+        };
+
+        let rules = match validation_rules(field_name, ctl_args) {
+            Ok(rules) => rules,
+            Err(err) => return err.to_compile_error().into(),
+        };
+        let mut has_async_validator = false;
+        if let Some(binding) = async_validator(field_name, ctl_args, &rules) {
+            match binding {
+                Ok(binding) => async_validators.push(binding),
+                Err(err) => return err.to_compile_error().into(),
+            }
+            has_async_validator = true;
+        }
+        // `show_if`/`enable_if`: hidden fields are neither validated nor serialized
+        let mut visible = None;
+        let condition_kv: HashMap<String, String> = ctl_args.to_string_kv().into_iter().collect();
+        for attr in ["show_if", "enable_if"] {
+            let expr = match condition_kv.get(attr) {
+                Some(expr) => expr,
+                None => continue,
+            };
+            let (source, condition) = match field_condition(field_name, attr, expr) {
+                Ok(condition) => condition,
+                Err(err) => return err.to_compile_error().into(),
+            };
+            if !fields.iter().any(|f| f.field_name == source) {
+                return Error::new_spanned(
+                    field_name,
+                    format!("#[field]: `{attr}` refers to unknown field `{source}`"),
+                )
+                .to_compile_error()
+                .into();
+            }
+            let apply = if attr == "show_if" {
+                visible = Some(quote! {
+                    #condition.matches(&workflow_ux::validation::Validatable::validation_value(&self.#source))
+                });
+                if has_async_validator {
+                    let binding = async_validator_ident(field_name);
+                    quote! {
+                        let validator = #binding.clone();
+                        Box::new(move |state| {
+                            workflow_ux::condition::show_field(&target, state)?;
+                            validator.set_active(state)
+                        })
+                    }
+                } else {
+                    quote! { Box::new(move |state| workflow_ux::condition::show_field(&target, state)) }
+                }
+            } else {
+                quote! { Box::new(move |state| workflow_ux::condition::enable_field(&target, state)) }
+            };
+            field_conditions.push(quote! {
+                layout._conditions.bind(&layout.#source, #condition, {
+                    let target = workflow_ux::prelude::JsCast::unchecked_into::<web_sys::Element>(layout.#field_name.element());
+                    #apply
+                })?;
+            });
+        }
+        let when_visible = |tokens: proc_macro2::TokenStream| match &visible {
+            Some(visible) => quote! {
+                if #visible {
+                    #tokens
+                }
+            },
+            None => tokens,
+        };
+
+        if layout != Layout::Stage && field.args.contains_key("stage") {
+            return Error::new_spanned(
+                field_name,
+                "#[stage]: stage attributes are only supported on the fields of a #[stage] layout",
+            )
+            .to_compile_error()
+            .into();
+        }
+
+        if layout == Layout::Stage {
+            // the values of the stages are stored side by side
+            field_schemas.push(quote! { .merge(<#type_name>::schema()) });
+        } else if let Some(schema) = field_schema(field, ctl_args, layout_args, &rules) {
+            let key = field_name.to_string();
+            field_schemas.push(quote! { .field(#key, #schema) });
+        }
+
+        if LOADABLE_CONTROLS.contains(&field.type_name_str_lower_case.as_str()) {
+            let key = &field_key;
+            field_loaders.push(with_control(quote! {
+                if let Some(value) = data.values.get(#key) {
+                    workflow_ux::form::FormValue::load_form_value(control, #key, value)?;
+                }
+            }));
+            field_collectors.push(when_visible(with_control(quote! {
+                if sensitive || !workflow_ux::form::FormValue::is_sensitive(control) {
+                    data.add(#key, workflow_ux::form::FormValue::form_value(control));
+                }
+            })));
+        } else if layout != Layout::Stage && is_nested_layout(field) {
+            let key = &field_key;
+            field_loaders.push(with_control(quote! {
+                if let Some(values) = data.get_object_json(#key)? {
+                    let nested = workflow_ux::form::FormData { id: None, values };
+                    workflow_ux::layout::DefaultFunctions::load_form_data(control, &nested)?;
+                }
+            }));
+            field_collectors.push(when_visible(with_control(quote! {
+                let mut nested = workflow_ux::form::FormData::new(None);
+                workflow_ux::layout::DefaultFunctions::collect_form_data(control, &mut nested, sensitive)?;
+                data.add_object_json(#key, &nested.values)?;
+            })));
+        }
+        if field.type_name_str_lower_case == "repeater" {
+            field_validators.push(when_visible(with_control(quote! {
+                valid &= control.validate()?;
+            })));
+        }
+        if !rules.is_empty() {
+            field_validators.push(when_visible(with_control(quote! {
+                valid &= workflow_ux::validation::Validatable::validate_rules(control, &[#( #rules ),*])?;
+            })));
+        }
+
+        let ctl_attrs_kv: Vec<(String, String)> = ctl_args
+            .to_string_kv()
+            .into_iter()
+            .filter(|(k, _)| {
+                !["custom", "async_validator", "debounce", "show_if", "enable_if", "span", "row"].contains(&k.as_str())
+            })
+            .collect();
         let ctl_attrs_k: Vec<String> = ctl_attrs_kv.iter().map(|item| item.0.to_string()).collect();
         let ctl_attrs_v: Vec<String> = ctl_attrs_kv.iter().map(|item| item.1.to_string()).collect();
-        let layout_attrs_kv = layout_args.to_string_kv();
+        // grid placement (`#[field(span = 2, row = "name")]`) belongs to the layout
+        let mut layout_attrs_kv = layout_args.to_string_kv();
+        if layout == Layout::Stage {
+            layout_attrs_kv.retain(|(k, _)| !STAGE_ATTRIBUTES.contains(&k.as_str()));
+        }
+        for (k, v) in ctl_args.to_string_kv() {
+            if (k == "span" || k == "row") && !layout_attrs_kv.iter().any(|(lk, _)| *lk == k) {
+                layout_attrs_kv.push((k, v));
+            }
+        }
         let layout_attrs_k: Vec<String> = layout_attrs_kv
             .iter()
             .map(|item| item.0.to_string())
@@ -390,17 +877,19 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
             .map(|item| item.1.to_string())
             .collect();
 
-        let mut append_field_element = quote! {
-            let child = #field_name.element();
-            _layout.append_child(&child, &layout_attributes, &docs)?;
-        };
-
-        if let Some(Some(skip)) = ctl_args.get("skip") {
-            let skip = skip.to_token_stream().to_string().replace('"', "");
-            if skip.eq("true") {
-                append_field_element = quote! {};
+        let append_field_element = if skip {
+            quote! {}
+        } else if registered {
+            quote! {
+                let child = #field_name.element();
+                _layout.insert_at(usize::MAX, #field_key, &#field_name, &child, &layout_attributes, &docs)?;
             }
-        }
+        } else {
+            quote! {
+                let child = #field_name.element();
+                _layout.append_keyed_child(#field_key, &child, &layout_attributes, &docs)?;
+            }
+        };
 
         //println!("ctl: {}, ctl_attrs_k:{:#?}, ctl_attrs_v:{:#?}", field.type_name_str_lower_case, ctl_attrs_k, ctl_attrs_v);
 
@@ -452,7 +941,8 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
                         // println!("********* ATTRIBUTE LIST: {:#?}",attr_list);
                         // println!("********* ATTRIBUTE MAP: {:#?}",attributes);
                         let docs : Vec<&str> = vec![#( #docs ), *];
-                        let #field_name = #type_name::new(&_layout, &ctl_attributes, &docs)?;  // pane-ctl
+                        // `<T>::new` allows generic controls such as `Repeater<Section>`
+                        let #field_name = <#type_name>::new(&_layout, &ctl_attributes, &docs)?;  // pane-ctl
                         #append_field_element
                         #field_name
                     };
@@ -577,6 +1067,8 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
     let mut init_extra_props_def = quote! {};
     let mut layout_loading = quote! {};
     let mut layout_binding = quote! {};
+    let mut draft_fn = quote! {};
+    let mut dirty_tracker_fn = quote! {};
     let impl_def = quote! {
         unsafe impl #struct_params Send for #struct_name #struct_params{}
         unsafe impl #struct_params Sync for #struct_name #struct_params{}
@@ -588,6 +1080,12 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
             init_extra_props = quote! {
                 pub _footer: workflow_ux::form_footer::FormFooter,
             };
+            // nested layouts are covered by the tracker of the form root
+            dirty_tracker_fn = quote! {
+                fn dirty_tracker(&self) -> Option<workflow_ux::dirty::DirtyTracker> {
+                    Some(self._dirty.clone())
+                }
+            };
             field_idents.push(Ident::new("_footer", Span::call_site()));
             field_initializers.push(quote! {
                 let mut _footer = {
@@ -601,10 +1099,56 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
                 };
             });
 
+            // `#[form(draft = "key", draft_max_age = seconds)]`
+            let draft_attributes: HashMap<String, String> =
+                layout_attributes.to_string_kv().into_iter().collect();
+            let mut draft_binding = quote! {};
+            if let Some(key) = draft_attributes.get("draft") {
+                let max_age = match draft_attributes.get("draft_max_age").map(|v| v.parse::<u64>()) {
+                    Some(Ok(max_age)) => quote! { Some(#max_age) },
+                    Some(Err(_)) => {
+                        return Error::new_spanned(
+                            struct_name,
+                            "#[form]: `draft_max_age` expects an age in seconds",
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+                    None => quote! { Some(workflow_ux::draft::Draft::DEFAULT_MAX_AGE) },
+                };
+                init_extra_props = quote! {
+                    pub _footer: workflow_ux::form_footer::FormFooter,
+                    _draft: workflow_ux::draft::Draft,
+                };
+                field_idents.push(Ident::new("_draft", Span::call_site()));
+                field_initializers.push(quote! {
+                    let _draft = workflow_ux::draft::Draft::new(#key, #max_age);
+                });
+                draft_fn = quote! {
+                    fn draft(&self) -> Option<workflow_ux::draft::Draft> {
+                        Some(self._draft.clone())
+                    }
+                };
+                // the layout lock is released before binding the draft
+                draft_binding = quote! {
+                    let draft = layout_clone.lock().expect(&format!("Unable to lock form {} for draft binding.", #struct_name_string))._draft.clone();
+                    draft.bind(&layout_clone)?;
+                };
+            }
+
             layout_binding = quote! ({
                 let layout_clone = view.layout();
-                let mut locked = layout_clone.lock().expect(&format!("Unable to lock form {} for footer binding.", #struct_name_string));
-                locked._footer.bind_layout(#struct_name_string.to_string(), view.clone())?;
+                let dirty = {
+                    let mut locked = layout_clone.lock().expect(&format!("Unable to lock form {} for footer binding.", #struct_name_string));
+                    locked._footer.bind_layout(#struct_name_string.to_string(), view.clone())?;
+                    locked._footer.bind_dirty_tracker(&locked._dirty)?;
+                    view.bind_dirty_tracker(&locked._dirty)?;
+                    locked._dirty.clone()
+                };
+                // only the form root is tracked; values set by
+                // `FormHandler::load()` are the clean state
+                dirty.bind(&layout_clone)?;
+                #draft_binding
             });
 
             init_helper_def = quote! {
@@ -626,23 +1170,70 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
         }
         Layout::Section => quote! { workflow_ux::layout::ElementLayoutStyle::Section },
         Layout::Stage => {
+            let mut stage_active = Vec::new();
+            let mut stage_next = Vec::new();
+            let mut stage_titles = Vec::new();
+            let mut stage_icons = Vec::new();
+            let mut stepper = false;
+            for field in fields.iter() {
+                match stage_predicates(field) {
+                    Ok((active, next)) => {
+                        stage_active.push(active);
+                        stage_next.push(next);
+                    }
+                    Err(err) => return err.to_compile_error().into(),
+                }
+                match stage_step(field) {
+                    Ok(Some((title, icon))) => {
+                        stepper = true;
+                        stage_titles.push(title);
+                        stage_icons.push(icon);
+                    }
+                    Ok(None) => {
+                        stage_titles.push(quote! { None });
+                        stage_icons.push(quote! { None });
+                    }
+                    Err(err) => return err.to_compile_error().into(),
+                }
+            }
+            // the stepper is shown once a stage declares `#[stage(title, icon)]`,
+            // `#[stage(navigable)]` allows returning to the visited stages
+            let stepper = if stepper {
+                quote! { Some(workflow_ux::stepper::Stepper::try_new()?) }
+            } else {
+                quote! { None }
+            };
+            let navigable = matches!(
+                layout_attributes.to_string_kv().into_iter().find(|(k, _)| k == "navigable"),
+                Some((_, v)) if v != "false"
+            );
+
             init_helper = quote! {
+                layout.init_stepper()?;
                 layout.init_footer()?;
                 layout.set_stage_index(0)?;
             };
+            // shared by all clones of the layout, i.e. the footer handlers
             init_extra_props = quote! {
-                _stage_index:usize,
+                _stage_index:std::sync::Arc<std::sync::Mutex<usize>>,
                 _stages:Vec<&'static str>,
+                _stage_history:std::sync::Arc<std::sync::Mutex<Vec<usize>>>,
+                _stepper:Option<workflow_ux::stepper::Stepper>,
             };
 
             init_extra_props_def = quote! {
-                _stage_index: 0,
+                _stage_index: std::sync::Arc::new(std::sync::Mutex::new(0)),
                 _stages: Vec::from([ #( #field_idents_str ),* ]),
+                _stage_history: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+                _stepper: #stepper,
             };
 
             init_helper_def = quote! {
+                pub fn stage_index(&self) -> workflow_ux::result::Result<usize>{
+                    Ok(*self._stage_index.lock()?)
+                }
                 pub fn validate_stage(&self)->workflow_ux::result::Result<bool>{
-                    let stage_name = self._stages[self._stage_index];
+                    let stage_name = self._stages[self.stage_index()?];
                     match stage_name{
                         #( #field_idents_str => {
                             return Ok(self.#field_idents.validate_stage()?);
@@ -675,7 +1266,7 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
                     };
                     */
 
-                    let next_stage_name = self._stages[self._stage_index];
+                    let next_stage_name = self._stages[self.stage_index()?];
                     let mut result = false;
 
                     #(
@@ -696,48 +1287,185 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
 
                     Ok(result)
                 }
-                pub fn show_prev_stage(&mut self) -> workflow_ux::result::Result<bool>{
-                    //if !self.validate_stage()?{
-                    //    return Ok(false);
-                    //}
-                    if self._stage_index == 0{
-                        Ok(false)
-                    }else{
-                        let index = self.set_stage_index(self._stage_index - 1)?;
-                        Ok(index)
+                /// Returns to the previously visited stage
+                pub fn show_prev_stage(&self) -> workflow_ux::result::Result<bool>{
+                    let previous = self._stage_history.lock()?.last().copied();
+                    match previous{
+                        Some(index) => self.set_stage_index(index),
+                        None => Ok(false)
                     }
                 }
-                pub fn show_next_stage(&mut self) -> workflow_ux::result::Result<bool>{
+                pub fn show_next_stage(&self) -> workflow_ux::result::Result<bool>{
                     if !self.validate_stage()?{
                         return Ok(false);
                     }
-                    //if this is last stage
-                    if self._stage_index+1 == self._stages.len(){
-                        Ok(false)
-                    }else{
-                        Ok(self.set_stage_index(self._stage_index + 1)?)
+                    self.advance_stage()
+                }
+                /// Validates the current stage including its async validators
+                /// (see [`workflow_ux::validation::validate_layout`]).
+                pub async fn validate_current_stage(&self) -> workflow_ux::result::Result<bool>{
+                    let stage_name = self._stages[self.stage_index()?];
+                    let valid = match stage_name{
+                        #( #field_idents_str => {
+                            workflow_ux::validation::validate_layout(&self.#field_idents).await?
+                        } ),*
+
+                        _=>true
+                    };
+                    if let Some(stepper) = &self._stepper {
+                        let position = self._stage_history.lock()?.len();
+                        if valid {
+                            stepper.clear_error(position)?;
+                        }else{
+                            stepper.mark_error(position)?;
+                        }
+                    }
+                    Ok(valid)
+                }
+                /// Data of the visited stages and the current stage,
+                /// evaluated by the stage `active`/`next` predicates
+                pub fn stage_data(&self) -> workflow_ux::result::Result<workflow_ux::form::FormData>{
+                    let mut visited = self._stage_history.lock()?.clone();
+                    visited.push(self.stage_index()?);
+                    let mut data = workflow_ux::form::FormData::new(None);
+                    for index in visited{
+                        match self._stages[index]{
+                            #( #field_idents_str => {
+                                workflow_ux::layout::DefaultFunctions::collect_form_data(&self.#field_idents, &mut data, false)?;
+                            } ),*
+
+                            _=>{}
+                        };
                     }
+                    Ok(data)
                 }
-                pub fn set_stage_index(&mut self, index:usize) -> workflow_ux::result::Result<bool>{
-                    self._stage_index = index;
+                /// Index of the stage following the current one on the effective path
+                #[allow(unused_variables)]
+                pub fn next_stage_index(&self, data: &workflow_ux::form::FormData) -> workflow_ux::result::Result<Option<usize>>{
+                    self.next_stage_index_from(self.stage_index()?, data)
+                }
+                /// Index of the stage following `current` on the effective path
+                #[allow(unused_variables)]
+                fn next_stage_index_from(&self, current: usize, data: &workflow_ux::form::FormData) -> workflow_ux::result::Result<Option<usize>>{
+                    let next: Option<&'static str> = match self._stages[current]{
+                        #( #field_idents_str => #stage_next, )*
+                        _=>None
+                    };
+                    if let Some(next) = next{
+                        return match self._stages.iter().position(|name| *name == next){
+                            Some(index) => Ok(Some(index)),
+                            None => Err(workflow_ux::error::Error::String(format!("unknown next stage `{}`", next)))
+                        };
+                    }
+                    for index in current+1..self._stages.len(){
+                        let active = match self._stages[index]{
+                            #( #field_idents_str => #stage_active, )*
+                            _=>true
+                        };
+                        if active{
+                            return Ok(Some(index));
+                        }
+                    }
+                    Ok(None)
+                }
+                fn advance_stage(&self) -> workflow_ux::result::Result<bool>{
+                    let data = self.stage_data()?;
+                    match self.next_stage_index(&data)?{
+                        Some(index) => self.set_stage_index(index),
+                        //if this is last stage
+                        None => Ok(false)
+                    }
+                }
+                /// Activates the stage; returning to a visited stage drops the
+                /// stages visited after it, other stages extend the history
+                pub fn set_stage_index(&self, index:usize) -> workflow_ux::result::Result<bool>{
+                    let is_first = {
+                        let mut stage_index = self._stage_index.lock()?;
+                        let mut history = self._stage_history.lock()?;
+                        if let Some(position) = history.iter().position(|i| *i == index){
+                            history.truncate(position);
+                        }else if index != *stage_index{
+                            history.push(*stage_index);
+                        }
+                        *stage_index = index;
+                        history.is_empty()
+                    };
 
                     let footer = self._layout.get_stage_footer()?;
-                    if self._stage_index == 0 {
+                    if is_first {
                         footer.disable_btn("previous");
                     }else{
                         footer.enable_btn("previous");
                     }
 
                     //if this is last stage
-                    if self._stage_index+1 == self._stages.len() {
+                    let data = self.stage_data()?;
+                    if self.next_stage_index(&data)?.is_none() {
                         footer.show_btn("submit");
                     }else{
                         footer.hide_btn("submit");
                         footer.show_btn("next");
                     }
 
+                    self.render_stepper()?;
                     Ok(self.update_stage_visibility()?)
                 }
+                /// Stage indexes of the effective path: the visited stages, the
+                /// current stage and the stages that follow it given the stage data
+                pub fn stage_path(&self) -> workflow_ux::result::Result<Vec<usize>>{
+                    let data = self.stage_data()?;
+                    let mut path = self._stage_history.lock()?.clone();
+                    let mut index = Some(self.stage_index()?);
+                    while let Some(i) = index{
+                        if path.contains(&i){
+                            break;
+                        }
+                        path.push(i);
+                        index = self.next_stage_index_from(i, &data)?;
+                    }
+                    Ok(path)
+                }
+                /// Inserts the stepper showing the `#[stage(title, icon)]` of the stages
+                pub fn init_stepper(&self) -> workflow_ux::result::Result<()>{
+                    let stepper = match &self._stepper{
+                        Some(stepper) => stepper,
+                        None => return Ok(())
+                    };
+                    self._layout.element().insert_adjacent_element("beforebegin", &stepper.element())?;
+                    stepper.set_navigable(#navigable)?;
+                    let this = self.clone();
+                    stepper.on_select(Box::new(move |position| -> workflow_ux::result::Result<()> {
+                        let index = this._stage_history.lock()?.get(position).copied();
+                        if let Some(index) = index{
+                            this.set_stage_index(index)?;
+                        }
+                        Ok(())
+                    }))
+                }
+                pub fn render_stepper(&self) -> workflow_ux::result::Result<()>{
+                    let stepper = match &self._stepper{
+                        Some(stepper) => stepper,
+                        None => return Ok(())
+                    };
+                    let completed = self._stage_history.lock()?.len();
+                    let mut steps = Vec::new();
+                    for (position, index) in self.stage_path()?.into_iter().enumerate(){
+                        let (title, icon): (Option<String>, Option<workflow_ux::icon::Icon>) = match self._stages[index]{
+                            #( #field_idents_str => (#stage_titles, #stage_icons), )*
+                            _=>(None, None)
+                        };
+                        let title = title.unwrap_or_else(|| workflow_ux::prelude::i18n("Step [N]").replace("[N]", &(position + 1).to_string()));
+                        let state = if position < completed {
+                            workflow_ux::stepper::StepState::Completed
+                        }else if position == completed {
+                            workflow_ux::stepper::StepState::Current
+                        }else{
+                            workflow_ux::stepper::StepState::Pending
+                        };
+                        steps.push(workflow_ux::stepper::Step::new(title).with_icon(icon).with_state(state));
+                    }
+                    stepper.set_steps(steps)
+                }
                 pub fn init_footer(&self) -> workflow_ux::result::Result<()> {
                     self._layout.init_footer()?;
                     let footer = self._layout.get_stage_footer()?;
@@ -753,7 +1481,8 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
                     //let len = self._stages.len();
                     //let mut stage_index = 0;
 
-                    let mut this = self.clone();
+                    // clones share the stage state
+                    let this = self.clone();
                     let closure = Closure::wrap(Box::new(move |event: workflow_ux::controls::stage_footer::StageFooterBtnEvent| {
 
                         //trace!("footer button click: {:#?}", event);
@@ -762,15 +1491,41 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
                         match btn.as_str(){
                             "next"=>{
                                 workflow_log::log_trace!("footer next btn clicked");
-                                this.show_next_stage().expect("footer next btn click failed");
+                                let this = this.clone();
+                                workflow_core::task::wasm::dispatch(async move {
+                                    let index = this.stage_index();
+                                    match this.validate_current_stage().await {
+                                        // the stage may have changed during validation
+                                        Ok(true) if this.stage_index().ok() == index.ok() => {
+                                            if let Err(err) = this.advance_stage() {
+                                                workflow_log::log_error!("footer next btn click failed: {}", err);
+                                            }
+                                        },
+                                        Ok(_) => {},
+                                        Err(err) => workflow_log::log_error!("footer next btn validation failed: {}", err),
+                                    }
+                                });
                             },
                             "previous"=>{
                                 workflow_log::log_trace!("footer previous btn clicked");
-                                this.show_prev_stage().expect("footer prev btn click failed");
+                                if let Err(err) = this.show_prev_stage() {
+                                    workflow_log::log_error!("footer prev btn click failed: {}", err);
+                                }
                             },
                             "submit"=>{
                                 workflow_log::log_trace!("footer submit btn clicked");
-                                this.submit().expect("footer submit btn click failed");
+                                let this = this.clone();
+                                workflow_core::task::wasm::dispatch(async move {
+                                    match this.validate_current_stage().await {
+                                        Ok(true) => {
+                                            if let Err(err) = this.submit() {
+                                                workflow_log::log_error!("footer submit btn click failed: {}", err);
+                                            }
+                                        },
+                                        Ok(false) => {},
+                                        Err(err) => workflow_log::log_error!("footer submit btn validation failed: {}", err),
+                                    }
+                                });
                             },
                             _=>{
 
@@ -860,6 +1615,11 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
 
                 #( #field_initializers ) *
 
+                let _validators = workflow_ux::validation::AsyncValidators::default();
+                #( #async_validators )*
+                let _dirty = workflow_ux::dirty::DirtyTracker::default();
+                let _conditions = workflow_ux::condition::Conditions::default();
+
                 if !hooks_used.is_empty() {
                     let unused = hooks_used.into_iter().collect::<Vec<String>>().join(",");
                     workflow_log::log_error!("{}",workflow_ux::error::Error::MissingLayoutBindings(#struct_name_string.into(), unused));
@@ -868,11 +1628,15 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
                 // let el = #struct_name :: #struct_params{
                 let mut layout = #struct_name {
                     _layout,
+                    _validators,
+                    _dirty,
+                    _conditions,
                     // #init_extra_props_def
                     #( #field_idents ),*
                 };
 
                 layout.init()?;
+                #( #field_conditions )*
 
                 // #init_helper
 
@@ -932,14 +1696,23 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
 
                 #( #field_initializers ) *
 
+                let _validators = workflow_ux::validation::AsyncValidators::default();
+                #( #async_validators )*
+                let _dirty = workflow_ux::dirty::DirtyTracker::default();
+                let _conditions = workflow_ux::condition::Conditions::default();
+
                 // let el = #struct_name :: #struct_params{
                 let mut layout = #struct_name {
                     _layout,
+                    _validators,
+                    _dirty,
+                    _conditions,
                     #init_extra_props_def
                     #( #field_idents ),*
                 };
 
                 layout.init()?;
+                #( #field_conditions )*
 
                 #init_helper
 
@@ -950,10 +1723,123 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
         },
     };
 
+    let validate_fn = if field_validators.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn validate(&self) -> workflow_ux::result::Result<bool> {
+                let mut valid = true;
+                #( #field_validators )*
+                Ok(valid)
+            }
+        }
+    };
+
+    let load_form_data_fn = match layout {
+        // stages are layouts themselves, each loads the keys of its own controls
+        Layout::Stage => quote! {
+            fn load_form_data(&self, data: &workflow_ux::form::FormData) -> workflow_ux::result::Result<()> {
+                #( workflow_ux::layout::DefaultFunctions::load_form_data(&self.#field_idents, data)?; )*
+                Ok(())
+            }
+        },
+        _ if !field_loaders.is_empty() => quote! {
+            fn load_form_data(&self, data: &workflow_ux::form::FormData) -> workflow_ux::result::Result<()> {
+                #( #field_loaders )*
+                Ok(())
+            }
+        },
+        _ => quote! {},
+    };
+
+    let schema_fn = match layout {
+        Layout::Html => quote! {},
+        _ => {
+            let title = match layout_attributes.to_string_kv().into_iter().find(|(k, _)| k == "title") {
+                Some((_, title)) => quote! { Some(#title) },
+                None => quote! { None },
+            };
+            quote! {
+                /// JSON Schema of the `FormData` collected from this layout
+                pub fn schema() -> workflow_ux::schema::Value {
+                    workflow_ux::schema::LayoutSchema::new(#title)
+                        #( #field_schemas )*
+                        .build()
+                }
+            }
+        }
+    };
+
+    // html layouts are bound to their markup and can not be created at runtime
+    let dynamic_layout_impl = match layout {
+        Layout::Html => quote! {},
+        _ => quote! {
+            impl #struct_params workflow_ux::layout::DynamicLayout for #struct_name #struct_params {
+                fn create(parent_layout: &ElementLayout, attributes: &Attributes, docs: &Docs) -> workflow_ux::result::Result<Self> {
+                    Self::new(parent_layout, attributes, docs)
+                }
+            }
+        },
+    };
+
+    let collect_form_data_fn = match layout {
+        Layout::Stage => quote! {
+            fn collect_form_data(&self, data: &mut workflow_ux::form::FormData, sensitive: bool) -> workflow_ux::result::Result<()> {
+                #( workflow_ux::layout::DefaultFunctions::collect_form_data(&self.#field_idents, data, sensitive)?; )*
+                Ok(())
+            }
+        },
+        _ if !field_collectors.is_empty() => quote! {
+            fn collect_form_data(&self, data: &mut workflow_ux::form::FormData, sensitive: bool) -> workflow_ux::result::Result<()> {
+                #( #field_collectors )*
+                Ok(())
+            }
+        },
+        _ => quote! {},
+    };
+
+    // `title = "..."`, `icon = "..."` of a layout used as one of the
+    // `FormStages` - shown by the stepper; the stages hosted by a `#[stage]`
+    // layout are titled by `#[stage(title, icon)]` on their fields
+    let stage_info_fn = match layout {
+        Layout::Stage | Layout::Html => quote! {},
+        _ => {
+            let title = match layout_attributes.get("title") {
+                Some(Some(title)) => {
+                    let title = title.to_token_stream();
+                    quote! {
+                        fn stage_title(&self) -> Option<String> {
+                            Some(workflow_ux::prelude::i18n(#title))
+                        }
+                    }
+                }
+                _ => quote! {},
+            };
+            let icon = match layout_attributes.get("icon") {
+                Some(Some(icon)) => {
+                    let icon = icon.to_token_stream();
+                    quote! {
+                        fn stage_icon(&self) -> Option<workflow_ux::icon::Icon> {
+                            Some(workflow_ux::icon::Icon::css(#icon))
+                        }
+                    }
+                }
+                _ => quote! {},
+            };
+            quote! {
+                #title
+                #icon
+            }
+        }
+    };
+
     let ts = quote!{
 
         pub struct #struct_name #struct_params{
             _layout : ElementLayout,
+            _validators : workflow_ux::validation::AsyncValidators,
+            _dirty : workflow_ux::dirty::DirtyTracker,
+            _conditions : workflow_ux::condition::Conditions,
             #init_extra_props
             #( #fields ),*
         }
@@ -992,6 +1878,8 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
 
             #init_helper_def
 
+            #schema_fn
+
             // pub fn test() -> bool { true }
 
             // pub fn ident() -> &'static str {
@@ -999,7 +1887,25 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
             // }
         }
 
-        impl #struct_params   workflow_ux::layout::DefaultFunctions for #struct_name #struct_params{}
+        impl #struct_params   workflow_ux::layout::DefaultFunctions for #struct_name #struct_params{
+            #validate_fn
+
+            #load_form_data_fn
+
+            #collect_form_data_fn
+
+            #stage_info_fn
+
+            #draft_fn
+
+            #dirty_tracker_fn
+
+            fn async_validators(&self) -> Option<workflow_ux::validation::AsyncValidators> {
+                Some(self._validators.clone())
+            }
+        }
+
+        #dynamic_layout_impl
 
         impl #struct_params   workflow_ux::layout::Elemental for #struct_name #struct_params{
             fn element(&self) -> web_sys::Element {
@@ -1011,6 +1917,9 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
             fn clone(&self) -> #struct_name #struct_params {
                 #struct_name{
                     _layout : self._layout.clone(),
+                    _validators : self._validators.clone(),
+                    _dirty : self._dirty.clone(),
+                    _conditions : self._conditions.clone(),
                     #init_extra_props_def
                     #( #field_idents : self.#field_idents.clone()),*
                 }
@@ -1034,3 +1943,41 @@ pub fn macro_handler(layout: Layout, attr: TokenStream, item: TokenStream) -> To
 
     ts
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition(expr: &str) -> (String, String) {
+        let field_name = Ident::new("target", Span::call_site());
+        let (source, condition) = field_condition(&field_name, "show_if", expr).unwrap();
+        (source.to_string(), condition.to_string())
+    }
+
+    #[test]
+    fn value_conditions() {
+        let (source, eq) = condition("provider == Custom");
+        assert_eq!(source, "provider");
+        assert_eq!(eq, quote! { workflow_ux::condition::Condition::Eq("Custom".to_string()) }.to_string());
+        let (source, ne) = condition("provider != 'Custom'");
+        assert_eq!(source, "provider");
+        assert_eq!(ne, quote! { workflow_ux::condition::Condition::Ne("Custom".to_string()) }.to_string());
+    }
+
+    #[test]
+    fn truthy_conditions() {
+        let (source, truthy) = condition(" enabled ");
+        assert_eq!(source, "enabled");
+        assert_eq!(truthy, quote! { workflow_ux::condition::Condition::Truthy }.to_string());
+        let (source, falsy) = condition("!enabled");
+        assert_eq!(source, "enabled");
+        assert_eq!(falsy, quote! { workflow_ux::condition::Condition::Falsy }.to_string());
+    }
+
+    #[test]
+    fn invalid_conditions() {
+        let field_name = Ident::new("target", Span::call_site());
+        assert!(field_condition(&field_name, "show_if", "a b == c").is_err());
+        assert!(field_condition(&field_name, "show_if", "").is_err());
+    }
+}
@@ -1,17 +1,23 @@
 use proc_macro::TokenStream;
 
+mod form_model;
 mod layout;
 mod link;
 mod menu;
 mod module;
 mod view;
 
+/// `#[view(Eviction::Allow, hooks, keep_alive = <key>)]`
+///
+/// `keep_alive` opts the view into the container keep-alive cache, which
+/// is enabled by default; it has no effect on a container whose cache
+/// policy was set to `CachePolicy::Disabled`.
 #[proc_macro_attribute]
 pub fn view(_attr: TokenStream, item: TokenStream) -> TokenStream {
     view::view(_attr, item)
 }
 
-#[proc_macro_derive(HtmlView, attributes(evict_handler))]
+#[proc_macro_derive(HtmlView, attributes(evict_handler, view_hooks))]
 pub fn html_view(input: TokenStream) -> TokenStream {
     view::html_view(input)
 }
@@ -102,6 +108,11 @@ pub fn html_layout(attr: TokenStream, item: TokenStream) -> TokenStream {
     layout::macro_handler(layout::Layout::Html, attr, item)
 }
 
+#[proc_macro_derive(FormModel, attributes(form_model))]
+pub fn derive_form_model(input: TokenStream) -> TokenStream {
+    form_model::derive_form_model(input)
+}
+
 // ~
 
 #[proc_macro]
@@ -48,6 +48,8 @@ impl Parse for ParsableNamedField {
 
 struct Attributes {
     pub evict: TokenStream2,
+    pub hooks: bool,
+    pub keep_alive: Option<Option<Expr>>,
 }
 
 impl Parse for Attributes {
@@ -56,10 +58,29 @@ impl Parse for Attributes {
 
         let parsed = Punctuated::<Expr, Token![,]>::parse_terminated(input).unwrap();
 
+        let mut hooks = false;
+        let mut keep_alive = None;
+        let mut parsed = parsed.into_iter().collect::<Vec<_>>();
+        parsed.retain(|expr| match expr {
+            Expr::Path(path) if path.path.is_ident("hooks") => {
+                hooks = true;
+                false
+            }
+            Expr::Path(path) if path.path.is_ident("keep_alive") => {
+                keep_alive = Some(None);
+                false
+            }
+            Expr::Assign(assign) if is_ident(&assign.left, "keep_alive") => {
+                keep_alive = Some(Some(*assign.right.clone()));
+                false
+            }
+            _ => true,
+        });
+
         if parsed.len() > 1 {
             return Err(Error::new_spanned(
-                parsed,
-                format!("usage: #[view[(evict::Allow or evict::Disallow)]]"),
+                &parsed[1],
+                format!("usage: #[view[(evict::Allow or evict::Disallow)[, hooks][, keep_alive[ = <key>]]]]"),
             ));
         }
 
@@ -86,10 +107,16 @@ impl Parse for Attributes {
 
         Ok(Attributes {
             evict: evict.clone(),
+            hooks,
+            keep_alive,
         })
     }
 }
 
+fn is_ident(expr: &Expr, ident: &str) -> bool {
+    matches!(expr, Expr::Path(path) if path.path.is_ident(ident))
+}
+
 pub fn view(attr: TokenStream, item: TokenStream) -> TokenStream {
     // println!("panel attrs: {:#?}",attr);
     // let layout_attributes = parse_macro_input!(attr as Args);
@@ -133,7 +160,30 @@ pub fn view(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
 
-    let _evict = attributes.evict.clone(); //.to_token_stream();
+    let evict = attributes.evict.clone(); //.to_token_stream();
+    let hooks = if attributes.hooks {
+        view_hooks()
+    } else {
+        quote! {}
+    };
+    let keep_alive = match &attributes.keep_alive {
+        Some(key) => {
+            let cache_key = key.as_ref().map(|key| {
+                quote! {
+                    fn cache_key(&self) -> Option<String> {
+                        Some((#key).to_string())
+                    }
+                }
+            });
+            quote! {
+                fn keep_alive(&self) -> bool {
+                    true
+                }
+                #cache_key
+            }
+        }
+        None => quote! {},
+    };
 
     let ts = quote! {
 
@@ -148,6 +198,7 @@ pub fn view(attr: TokenStream, item: TokenStream) -> TokenStream {
             }
         }
 
+        #[workflow_ux::workflow_async_trait]
         impl workflow_ux::view::View for #struct_name {
 
             fn element(&self) -> web_sys::Element {
@@ -159,9 +210,11 @@ pub fn view(attr: TokenStream, item: TokenStream) -> TokenStream {
             fn typeid(&self) -> std::any::TypeId {
                 std::any::TypeId::of::<Self>()
             }
-            // fn evict(&self) -> workflow_ux::view::Eviction {
-            //     #evict
-            // }
+            async fn eviction(self: std::sync::Arc<Self>) -> workflow_ux::result::Result<workflow_ux::view::Eviction> {
+                Ok(#evict)
+            }
+            #keep_alive
+            #hooks
         }
 
     };
@@ -236,6 +289,16 @@ pub fn html_view(item: TokenStream) -> TokenStream {
         }
     };
 
+    let hooks = if ast
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident("view_hooks"))
+    {
+        view_hooks()
+    } else {
+        quote! {}
+    };
+
     let name = struct_name.to_string();
     let html_missing_msg = format!("{} requires inner html view", name);
     let evict_msg = format!("{} evict", name);
@@ -271,16 +334,24 @@ pub fn html_view(item: TokenStream) -> TokenStream {
                 std::any::TypeId::of::<Self>()
             }
 
+            async fn eviction(self:Arc<Self>) -> Result<workflow_ux::view::Eviction>{
+                if (self.clone() as Arc<dyn workflow_ux::view::Evict>).evict().await?{
+                    Ok(workflow_ux::view::Eviction::Allow)
+                }else{
+                    Ok(workflow_ux::view::Eviction::Disallow)
+                }
+            }
+
             async fn evict(self:Arc<Self>) ->  Result<()>{
                 log_info!(#evict_msg);
 
-                if (self.clone() as Arc<dyn workflow_ux::view::Evict>).evict().await?{
-                    let html = self.get_html().unwrap();
-                    html.evict().await?;
-                }
+                let html = self.get_html().unwrap();
+                html.evict().await?;
 
                 Ok(())
             }
+
+            #hooks
         }
 
 
@@ -303,3 +374,30 @@ pub fn html_view(item: TokenStream) -> TokenStream {
 
     ts.into()
 }
+
+/// `View` lifecycle hooks delegating to the `ViewHooks` impl of the struct
+fn view_hooks() -> TokenStream2 {
+    quote! {
+        async fn on_show(self: std::sync::Arc<Self>) -> workflow_ux::result::Result<()> {
+            <Self as workflow_ux::view::ViewHooks>::on_show(self).await
+        }
+        async fn on_hide(self: std::sync::Arc<Self>) -> workflow_ux::result::Result<()> {
+            <Self as workflow_ux::view::ViewHooks>::on_hide(self).await
+        }
+        fn on_resize(&self, width: f64, height: f64) -> workflow_ux::result::Result<()> {
+            <Self as workflow_ux::view::ViewHooks>::on_resize(self, width, height)
+        }
+        fn on_visibility(&self, visible: bool) -> workflow_ux::result::Result<()> {
+            <Self as workflow_ux::view::ViewHooks>::on_visibility(self, visible)
+        }
+        async fn on_focus_return(self: std::sync::Arc<Self>) -> workflow_ux::result::Result<()> {
+            <Self as workflow_ux::view::ViewHooks>::on_focus_return(self).await
+        }
+        async fn on_park(self: std::sync::Arc<Self>) -> workflow_ux::result::Result<()> {
+            <Self as workflow_ux::view::ViewHooks>::on_park(self).await
+        }
+        async fn on_restore(self: std::sync::Arc<Self>) -> workflow_ux::result::Result<()> {
+            <Self as workflow_ux::view::ViewHooks>::on_restore(self).await
+        }
+    }
+}
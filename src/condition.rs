@@ -0,0 +1,140 @@
+//!
+//! Conditional visibility and enabled state of layout fields,
+//! declared with the `show_if` and `enable_if` field attributes
+//! (i.e. `#[field(show_if = "provider == Custom")]`).
+//!
+
+use crate::controls::element_wrapper::BaseElementTrait;
+use crate::prelude::*;
+use crate::result::Result;
+use crate::validation::{show_message, Validatable};
+use workflow_wasm::callback::CallbackMap;
+use workflow_wasm::prelude::callback;
+
+/// Condition on the value of a source control
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    /// `field == value`
+    Eq(String),
+    /// `field != value`
+    Ne(String),
+    /// `field`: the value is set (non-empty, checked)
+    Truthy,
+    /// `!field`: the value is not set
+    Falsy,
+}
+
+fn is_truthy(value: &str) -> bool {
+    !value.is_empty() && value != "false"
+}
+
+impl Condition {
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            // checkboxes report an empty value when unchecked
+            Condition::Eq(expected) if expected == "true" || expected == "false" => {
+                is_truthy(value) == (expected == "true")
+            }
+            Condition::Ne(expected) if expected == "true" || expected == "false" => {
+                is_truthy(value) != (expected == "true")
+            }
+            Condition::Eq(expected) => value == expected,
+            Condition::Ne(expected) => value != expected,
+            Condition::Truthy => is_truthy(value),
+            Condition::Falsy => !is_truthy(value),
+        }
+    }
+}
+
+/// Shows or hides a field together with its form control wrapper;
+/// a hidden field also drops its validation message.
+pub fn show_field(element: &Element, show: bool) -> Result<()> {
+    if element.closest_form_control()?.is_some() {
+        element.show_form_control(show)?;
+    } else if show {
+        element.remove_attribute("hidden")?;
+    } else {
+        element.set_attribute("hidden", "true")?;
+    }
+    if !show {
+        show_message(element, None)?;
+    }
+    Ok(())
+}
+
+pub fn enable_field(element: &Element, enable: bool) -> Result<()> {
+    if enable {
+        element.remove_attribute("disabled")?;
+    } else {
+        element.set_attribute("disabled", "true")?;
+    }
+    Ok(())
+}
+
+/// Field conditions of a layout; keeps the source control listeners alive.
+#[derive(Clone, Default)]
+pub struct Conditions(Arc<Mutex<Vec<CallbackMap>>>);
+
+impl Conditions {
+    /// Applies `apply` with the result of `condition` now and each time the
+    /// value of `source` changes. DOM events are used, so that the `on_change`
+    /// callback of the source control remains available to the application.
+    pub fn bind<C>(
+        &self,
+        source: &C,
+        condition: Condition,
+        apply: Box<dyn Fn(bool) -> Result<()>>,
+    ) -> Result<()>
+    where
+        C: Validatable + Clone + 'static,
+    {
+        apply(condition.matches(&source.validation_value()))?;
+
+        let ctl = source.clone();
+        let callback = callback!(move |_event: web_sys::Event| -> Result<()> {
+            apply(condition.matches(&ctl.validation_value()))
+        });
+        let element = source.validation_element();
+        for event in ["changed", "change", "input"] {
+            element.add_event_listener_with_callback(event, callback.as_ref())?;
+        }
+        let callbacks = CallbackMap::new();
+        callbacks.retain(callback)?;
+        self.0.lock()?.push(callbacks);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn value_conditions() {
+        assert!(Condition::Eq("Custom".into()).matches("Custom"));
+        assert!(!Condition::Eq("Custom".into()).matches("Default"));
+        assert!(Condition::Ne("Custom".into()).matches("Default"));
+        assert!(!Condition::Ne("Custom".into()).matches("Custom"));
+    }
+
+    #[wasm_bindgen_test]
+    fn truthy_conditions() {
+        assert!(Condition::Truthy.matches("value"));
+        assert!(!Condition::Truthy.matches(""));
+        assert!(!Condition::Truthy.matches("false"));
+        assert!(Condition::Falsy.matches(""));
+        assert!(Condition::Falsy.matches("false"));
+        assert!(!Condition::Falsy.matches("true"));
+    }
+
+    #[wasm_bindgen_test]
+    fn boolean_conditions() {
+        // unchecked checkboxes report an empty value
+        assert!(Condition::Eq("false".into()).matches(""));
+        assert!(Condition::Eq("true".into()).matches("true"));
+        assert!(!Condition::Eq("true".into()).matches(""));
+        assert!(Condition::Ne("true".into()).matches(""));
+        assert!(!Condition::Ne("false".into()).matches(""));
+    }
+}
@@ -62,6 +62,16 @@ impl Checkbox {
         *self.value.lock().unwrap()
     }
 
+    pub fn set_value(&self, checked: bool) -> Result<()> {
+        if checked {
+            self.element_wrapper.element.set_attribute("checked", "")?;
+        } else {
+            self.element_wrapper.element.remove_attribute("checked")?;
+        }
+        *self.value.lock().unwrap() = checked;
+        Ok(())
+    }
+
     pub fn on_change(&self, callback: CallbackFnNoArgs) {
         *self.on_change_cb.lock().unwrap() = Some(callback);
     }
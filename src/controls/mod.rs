@@ -22,6 +22,7 @@ pub mod prelude;
 pub mod qr;
 pub mod radio;
 pub mod radio_btns;
+pub mod repeater;
 pub mod select;
 pub mod selector;
 pub mod stage_footer;
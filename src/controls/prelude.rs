@@ -12,6 +12,7 @@ pub use crate::controls::{
     qr::QRCode,
     radio::Radio,
     radio_btns::RadioBtns,
+    repeater::Repeater,
     select::*,
     selector::Selector,
     stage_footer::StageFooter,
@@ -0,0 +1,378 @@
+use crate::error::{error, Error};
+use crate::form::FormValue;
+use crate::icon::Icon;
+use crate::layout::DynamicLayout;
+use crate::prelude::*;
+use crate::result::Result;
+use crate::validation::validate_layout;
+use borsh::BorshDeserialize;
+use std::collections::BTreeMap;
+use workflow_core::id::Id;
+use workflow_wasm::callback::CallbackMap;
+use workflow_wasm::prelude::callback;
+
+pub static CSS: &str = include_str!("repeater.css");
+
+struct RepeaterEntry<T> {
+    id: Id,
+    element: Element,
+    item: T,
+}
+
+/// Inline list of sub-layouts (i.e. `Repeater<RecipientSection>` inside a `#[form]`)
+/// with add, remove and reorder buttons. The entries are stored in [`FormData`]
+/// as a [`FormDataValue::Json`] array holding the values of each entry,
+/// i.e. `[{"name":{"type":"string","value":"..."}}]` (see [`FormData::values`]).
+///
+/// Attributes: `add_label`, `min_items` and `max_items`.
+pub struct Repeater<T> {
+    pub layout: ElementLayout,
+    pub element_wrapper: ElementWrapper,
+    list: Element,
+    add_btn: Element,
+    entries: Arc<Mutex<Vec<RepeaterEntry<T>>>>,
+    min: usize,
+    max: Option<usize>,
+    callbacks: CallbackMap,
+}
+
+impl<T> Clone for Repeater<T> {
+    fn clone(&self) -> Self {
+        Self {
+            layout: self.layout.clone(),
+            element_wrapper: self.element_wrapper.clone(),
+            list: self.list.clone(),
+            add_btn: self.add_btn.clone(),
+            entries: self.entries.clone(),
+            min: self.min,
+            max: self.max,
+            callbacks: self.callbacks.clone(),
+        }
+    }
+}
+
+impl<T> Repeater<T>
+where
+    T: DynamicLayout + DefaultFunctions + Elemental + Clone + 'static,
+{
+    pub fn element(&self) -> Element {
+        self.element_wrapper.element.clone()
+    }
+
+    pub fn new(layout: &ElementLayout, attributes: &Attributes, _docs: &Docs) -> Result<Self> {
+        let element = create_el("div.workflow-repeater", vec![], None)?;
+        let list = create_el("div.workflow-repeater-items", vec![], None)?;
+        element.append_child(&list)?;
+        let add_label = attributes
+            .get("add_label")
+            .cloned()
+            .unwrap_or_else(|| i18n("Add"));
+        let add_btn = create_el("flow-btn.add", vec![("data-action", "add")], None)?;
+        add_btn.set_text_content(Some(&add_label));
+        element.append_child(&add_btn)?;
+
+        let min = item_count(attributes, "min_items")?.unwrap_or(0);
+        let max = item_count(attributes, "max_items")?;
+        if let Some(max) = max {
+            if max == 0 || max < min {
+                return Err(error!(
+                    "Repeater: `max_items` ({}) must be positive and at least `min_items` ({})",
+                    max, min
+                ));
+            }
+        }
+
+        let control = Self {
+            layout: layout.clone(),
+            element_wrapper: ElementWrapper::new(element),
+            list,
+            add_btn,
+            entries: Arc::new(Mutex::new(Vec::new())),
+            min,
+            max,
+            callbacks: CallbackMap::new(),
+        };
+        control.init()?;
+        for _ in 0..min {
+            control.add()?;
+        }
+        Ok(control)
+    }
+
+    fn init(&self) -> Result<()> {
+        // the handler is retained by `callbacks`, so it must not hold them
+        let this = Self {
+            callbacks: CallbackMap::new(),
+            ..self.clone()
+        };
+        let callback = callback!(move |event: web_sys::MouseEvent| -> Result<()> {
+            let target = match event.target().and_then(|t| t.dyn_into::<Element>().ok()) {
+                Some(target) => target,
+                None => return Ok(()),
+            };
+            // only act on the buttons of this repeater, not of a nested one
+            if target.closest(".workflow-repeater")? != Some(this.element()) {
+                return Ok(());
+            }
+            let action = match target.closest("[data-action]")? {
+                Some(el) => el.get_attribute("data-action").unwrap_or_default(),
+                None => return Ok(()),
+            };
+            if action == "add" {
+                this.add()?;
+                return this.notify_change();
+            }
+            let id = match target.closest(".workflow-repeater-item")? {
+                Some(el) => el.get_attribute("data-id").unwrap_or_default(),
+                None => return Ok(()),
+            };
+            let position = match this.position(&id)? {
+                Some(position) => position,
+                None => return Ok(()),
+            };
+            let changed = match action.as_str() {
+                "remove" => this.remove(position)?,
+                "order-up" if position > 0 => this.swap(position - 1, position)?,
+                "order-down" => this.swap(position, position + 1)?,
+                _ => false,
+            };
+            if changed {
+                this.notify_change()?;
+            }
+            Ok(())
+        });
+        self.element()
+            .add_event_listener_with_callback("click", callback.as_ref())?;
+        self.callbacks.retain(callback)?;
+        Ok(())
+    }
+
+    fn position(&self, id: &str) -> Result<Option<usize>> {
+        Ok(self
+            .entries
+            .lock()?
+            .iter()
+            .position(|entry| entry.id.to_string() == id))
+    }
+
+    /// Structural changes do not emit input events, this lets dirty
+    /// tracking and drafts of the parent form follow them.
+    fn notify_change(&self) -> Result<()> {
+        let mut init = web_sys::CustomEventInit::new();
+        init.bubbles(true);
+        let event = web_sys::CustomEvent::new_with_event_init_dict("change", &init)?;
+        self.element().dispatch_event(&event)?;
+        Ok(())
+    }
+
+    fn update_buttons(&self) -> Result<()> {
+        let len = self.len()?;
+        let can_add = self.max.map(|max| len < max).unwrap_or(true);
+        if can_add {
+            self.add_btn.remove_attribute("hidden")?;
+        } else {
+            self.add_btn.set_attribute("hidden", "true")?;
+        }
+        self.element()
+            .class_list()
+            .toggle_with_force("at-min", len <= self.min)?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.entries.lock()?.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    pub fn items(&self) -> Result<Vec<T>> {
+        Ok(self
+            .entries
+            .lock()?
+            .iter()
+            .map(|entry| entry.item.clone())
+            .collect())
+    }
+
+    /// Appends a new entry
+    pub fn add(&self) -> Result<T> {
+        if let Some(max) = self.max {
+            if self.len()? >= max {
+                return Err(error!("Repeater: maximum of {} entries reached", max));
+            }
+        }
+        let item = T::create(&self.layout, &Attributes::new(), &Docs::new())?;
+        let id = Id::new();
+        let element = create_el(
+            "div.workflow-repeater-item",
+            vec![("data-id", &id.to_string())],
+            None,
+        )?;
+        element.append_child(&item.element())?;
+        let actions = create_el("div.actions", vec![], None)?;
+        for action in ["order-up", "order-down", "remove"] {
+            let icon = Icon::css(format!("repeater-{action}")).element()?;
+            icon.set_attribute("data-action", action)?;
+            actions.append_child(&icon)?;
+        }
+        element.append_child(&actions)?;
+        self.list.append_child(&element)?;
+
+        self.entries.lock()?.push(RepeaterEntry {
+            id,
+            element,
+            item: item.clone(),
+        });
+        self.update_buttons()?;
+        Ok(item)
+    }
+
+    /// Removes the entry at `position`.  Returns `false` if there is no
+    /// such entry or the list holds no more than `min_items` entries.
+    pub fn remove(&self, position: usize) -> Result<bool> {
+        {
+            let mut entries = self.entries.lock()?;
+            if position >= entries.len() || entries.len() <= self.min {
+                return Ok(false);
+            }
+            let entry = entries.remove(position);
+            entry.element.remove();
+        }
+        self.update_buttons()?;
+        Ok(true)
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        for entry in self.entries.lock()?.drain(..) {
+            entry.element.remove();
+        }
+        self.update_buttons()
+    }
+
+    /// Swaps two adjacent entries (`a` < `b`).
+    /// Returns `false` if `b` is past the last entry.
+    fn swap(&self, a: usize, b: usize) -> Result<bool> {
+        let mut entries = self.entries.lock()?;
+        if b >= entries.len() {
+            return Ok(false);
+        }
+        self.list
+            .insert_before(&entries[b].element, Some(&entries[a].element))?;
+        entries.swap(a, b);
+        Ok(true)
+    }
+
+    /// Validates every entry; all entries are checked so that each shows its messages.
+    pub fn validate(&self) -> Result<bool> {
+        let mut valid = true;
+        for item in self.items()?.iter() {
+            valid &= item.validate()?;
+        }
+        Ok(valid)
+    }
+
+    /// Like [`Repeater::validate()`], also waiting for the async validators of the entries
+    pub async fn validate_async(&self) -> Result<bool> {
+        let mut valid = true;
+        for item in self.items()?.iter() {
+            valid &= validate_layout(item).await?;
+        }
+        Ok(valid)
+    }
+
+    pub fn form_data_list(&self, sensitive: bool) -> Result<Vec<FormData>> {
+        let mut list = Vec::new();
+        for item in self.items()?.iter() {
+            let mut data = FormData::new(None);
+            item.collect_form_data(&mut data, sensitive)?;
+            list.push(data);
+        }
+        Ok(list)
+    }
+
+    /// Replaces the entries with one entry per element of `list`
+    pub fn load_form_data_list(&self, list: &[FormData]) -> Result<()> {
+        self.clear()?;
+        for data in list.iter() {
+            self.add()?.load_form_data(data)?;
+        }
+        for _ in list.len()..self.min {
+            self.add()?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the `min_items`/`max_items` attribute
+fn item_count(attributes: &Attributes, name: &str) -> Result<Option<usize>> {
+    match attributes.get(name) {
+        Some(value) => match value.parse::<usize>() {
+            Ok(count) => Ok(Some(count)),
+            Err(_) => Err(error!(
+                "Repeater: `{}` expects a number, got `{}`",
+                name, value
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+impl<T> FormValue for Repeater<T>
+where
+    T: DynamicLayout + DefaultFunctions + Elemental + Clone + 'static,
+{
+    fn load_form_value(&self, name: &str, value: &FormDataValue) -> Result<()> {
+        let list = match value {
+            FormDataValue::Json(json) => serde_json::from_str::<Vec<BTreeMap<_, _>>>(json)
+                .map_err(|err| Error::FormDataFieldType(name.to_string(), err.to_string()))?
+                .into_iter()
+                .map(|values| FormData { id: None, values })
+                .collect(),
+            // lists stored before entries were kept as JSON
+            FormDataValue::Object(bytes) => <Vec<FormData>>::try_from_slice(bytes)?,
+            _ => {
+                return Err(Error::FormDataFieldType(
+                    name.to_string(),
+                    "a list of objects".into(),
+                ))
+            }
+        };
+        self.load_form_data_list(&list)
+    }
+
+    fn form_value(&self) -> FormDataValue {
+        let list = self.form_data_list(true).unwrap_or_else(|err| {
+            log_error!("Repeater: unable to collect entries: {}", err);
+            Vec::new()
+        });
+        let values = list.into_iter().map(|data| data.values).collect::<Vec<_>>();
+        let json = serde_json::to_string(&values).unwrap_or_else(|err| {
+            log_error!("Repeater: unable to serialize entries: {}", err);
+            "[]".into()
+        });
+        FormDataValue::Json(json)
+    }
+
+    /// Entries containing sensitive controls make the whole list sensitive.
+    /// All entries are created from `T`, so the first one decides.
+    fn is_sensitive(&self) -> bool {
+        let item = match self.entries.lock() {
+            Ok(entries) => match entries.first() {
+                Some(entry) => entry.item.clone(),
+                None => return false,
+            },
+            Err(_) => return true,
+        };
+        let mut all = FormData::new(None);
+        let mut safe = FormData::new(None);
+        match (
+            item.collect_form_data(&mut all, true),
+            item.collect_form_data(&mut safe, false),
+        ) {
+            (Ok(_), Ok(_)) => all != safe,
+            _ => true,
+        }
+    }
+}
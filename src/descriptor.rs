@@ -0,0 +1,481 @@
+//!
+//! Form layouts built at runtime from a JSON descriptor, i.e. forms defined
+//! by server configuration instead of the `#[form]` macro:
+//!
+//! ```json
+//! {
+//!     "title": "Account",
+//!     "submit": "Save",
+//!     "sections": [{
+//!         "title": "Contact",
+//!         "fields": [{
+//!             "name": "email",
+//!             "type": "input",
+//!             "label": "Email",
+//!             "docs": ["Used for notifications"],
+//!             "attributes": { "type": "email" },
+//!             "rules": { "required": true, "email": true }
+//!         }, {
+//!             "name": "plan",
+//!             "type": "select",
+//!             "options": [{ "value": "free", "label": "Free" }]
+//!         }]
+//!     }]
+//! }
+//! ```
+//!
+//! Supported control types are `input`, `textarea`, `checkbox`, `select`
+//! and `radio`.
+//!
+//! Unlike `#[form]` layouts, descriptor forms have no async validators,
+//! drafts or dirty tracking: the submit button is always enabled and
+//! [`DescriptorForm::on_submit()`] receives the values once the rules pass.
+//!
+
+use crate::controls::radio::FlowRadiosBase;
+use crate::error::{error, Error};
+use crate::form::FormValue;
+use crate::form_footer::FormFooter;
+use crate::markdown::markdown_to_html;
+use crate::prelude::*;
+use crate::result::Result;
+use crate::validation::{show_message, validate, Rule};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LayoutDescriptor {
+    pub title: Option<String>,
+    /// Submit button caption
+    pub submit: Option<String>,
+    #[serde(default)]
+    pub sections: Vec<SectionDescriptor>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SectionDescriptor {
+    pub title: Option<String>,
+    #[serde(default)]
+    pub docs: Vec<String>,
+    #[serde(default)]
+    pub fields: Vec<FieldDescriptor>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldDescriptor {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub control: String,
+    pub label: Option<String>,
+    #[serde(default)]
+    pub docs: Vec<String>,
+    /// Control attributes, i.e. `placeholder` or the `type` of an `input`
+    #[serde(default)]
+    pub attributes: BTreeMap<String, serde_json::Value>,
+    /// Options of `select` and `radio` controls
+    #[serde(default)]
+    pub options: Vec<OptionDescriptor>,
+    #[serde(default)]
+    pub rules: RulesDescriptor,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OptionDescriptor {
+    pub value: String,
+    pub label: Option<String>,
+}
+
+/// Validation rules, see [`Rule`]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RulesDescriptor {
+    pub required: bool,
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+    pub pattern: Option<String>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub email: bool,
+}
+
+impl LayoutDescriptor {
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+enum FieldControl {
+    Input(Input),
+    Textarea(Textarea),
+    Checkbox(Checkbox),
+    Select(Element),
+    Radio(Element),
+}
+
+impl FieldControl {
+    fn create(layout: &ElementLayout, field: &FieldDescriptor) -> Result<Self> {
+        let mut attributes = Attributes::new();
+        for (k, v) in field.attributes.iter() {
+            let v = match v {
+                serde_json::Value::String(v) => v.clone(),
+                v => v.to_string(),
+            };
+            attributes.insert(k.clone(), v);
+        }
+        let docs = Docs::new();
+
+        let control = match field.control.as_str() {
+            "input" => Self::Input(Input::new(layout, &attributes, &docs)?),
+            "textarea" => Self::Textarea(Textarea::new(layout, &attributes, &docs)?),
+            "checkbox" => Self::Checkbox(Checkbox::new(layout, &attributes, &docs)?),
+            "select" => Self::Select(choice_element(
+                "flow-select",
+                "flow-menu-item",
+                "value",
+                field,
+                &attributes,
+            )?),
+            "radio" => Self::Radio(choice_element(
+                "flow-radios",
+                "flow-radio",
+                "inputvalue",
+                field,
+                &attributes,
+            )?),
+            control => {
+                return Err(error!(
+                    "LayoutDescriptor: unsupported control type `{}` of field `{}`",
+                    control, field.name
+                ))
+            }
+        };
+        Ok(control)
+    }
+
+    fn element(&self) -> Element {
+        match self {
+            Self::Input(input) => input.element_wrapper.element.clone(),
+            Self::Textarea(textarea) => textarea.element_wrapper.element.clone(),
+            Self::Checkbox(checkbox) => checkbox.element(),
+            Self::Select(element) | Self::Radio(element) => element.clone(),
+        }
+    }
+
+    fn value(&self) -> FormDataValue {
+        match self {
+            Self::Input(input) => input.form_value(),
+            Self::Textarea(textarea) => textarea.form_value(),
+            Self::Checkbox(checkbox) => checkbox.form_value(),
+            Self::Select(element) => {
+                FormDataValue::String(element.unchecked_ref::<FlowMenuBase>().value())
+            }
+            Self::Radio(element) => {
+                FormDataValue::String(element.unchecked_ref::<FlowRadiosBase>().value())
+            }
+        }
+    }
+
+    /// Value checked against the validation rules; unchecked checkboxes are empty
+    fn validation_value(&self) -> String {
+        match self.value() {
+            FormDataValue::Bool(true) => "true".to_string(),
+            FormDataValue::Bool(false) => String::new(),
+            value => value.as_string().unwrap_or_default(),
+        }
+    }
+
+    fn load(&self, name: &str, value: &FormDataValue) -> Result<()> {
+        match self {
+            Self::Input(input) => input.load_form_value(name, value),
+            Self::Textarea(textarea) => textarea.load_form_value(name, value),
+            Self::Checkbox(checkbox) => checkbox.load_form_value(name, value),
+            Self::Select(element) | Self::Radio(element) => {
+                let value = value
+                    .as_string()
+                    .ok_or_else(|| Error::FormDataFieldType(name.to_string(), "a string".into()))?;
+                FieldHelper::set_attr(element, "selected", &value)?;
+                Ok(())
+            }
+        }
+    }
+
+    fn is_sensitive(&self) -> bool {
+        match self {
+            Self::Input(input) => input.is_sensitive(),
+            _ => false,
+        }
+    }
+}
+
+/// `<flow-select>` or `<flow-radios>` holding the options of `field`
+fn choice_element(
+    tag: &str,
+    item_tag: &str,
+    value_attr: &str,
+    field: &FieldDescriptor,
+    attributes: &Attributes,
+) -> Result<Element> {
+    let doc = document();
+    let element = doc.create_element(tag)?;
+    for option in field.options.iter() {
+        let item = doc.create_element(item_tag)?;
+        item.set_attribute(value_attr, &option.value)?;
+        item.set_inner_html(&i18n(option.label.as_ref().unwrap_or(&option.value)));
+        element.append_child(&item)?;
+    }
+
+    let mut selected = field.options.first().map(|option| option.value.clone());
+    for (k, v) in attributes.iter() {
+        if k.eq("value") {
+            selected = Some(v.clone());
+        } else {
+            element.set_attribute(k, v)?;
+        }
+    }
+    if let Some(selected) = selected {
+        element.set_attribute("selected", &selected)?;
+    }
+    Ok(element)
+}
+
+struct DescriptorField {
+    name: String,
+    control: FieldControl,
+    rules: Vec<Rule>,
+    pattern: Option<Regex>,
+}
+
+impl DescriptorField {
+    fn validate(&self) -> Result<bool> {
+        let value = self.control.validation_value();
+        let mut message = validate(&value, &self.rules)?;
+        if message.is_none() && !value.trim().is_empty() {
+            if let Some(pattern) = &self.pattern {
+                if !pattern.is_match(&value) {
+                    message = Some(i18n("Please enter a value in the required format"));
+                }
+            }
+        }
+        show_message(&self.control.element(), message.as_deref())?;
+        Ok(message.is_none())
+    }
+}
+
+/// Form built from a [`LayoutDescriptor`]
+#[derive(Clone)]
+pub struct DescriptorForm {
+    layout: ElementLayout,
+    fields: Arc<Vec<DescriptorField>>,
+    footer: FormFooter,
+    on_submit_cb: OptionalCallbackFn<FormData>,
+}
+
+unsafe impl Send for DescriptorForm {}
+unsafe impl Sync for DescriptorForm {}
+
+impl DescriptorForm {
+    pub fn from_json(parent: &Element, json: &str) -> Result<Self> {
+        Self::try_new(parent, &LayoutDescriptor::from_json(json)?)
+    }
+
+    pub fn try_new(parent: &Element, descriptor: &LayoutDescriptor) -> Result<Self> {
+        let layout = ElementLayout::try_inject(parent, ElementLayoutStyle::Form)?;
+        if let Some(title) = &descriptor.title {
+            let title_el = create_el("h2.layout-title", vec![], None)?;
+            title_el.set_inner_html(&i18n(title));
+            layout.element().append_child(&title_el)?;
+        }
+
+        let mut fields: Vec<DescriptorField> = Vec::new();
+        for section in descriptor.sections.iter() {
+            let mut section_attributes = Attributes::new();
+            if let Some(title) = &section.title {
+                section_attributes.insert("title".to_string(), i18n(title));
+            }
+            let section_layout =
+                ElementLayout::new(&layout, ElementLayoutStyle::Section, &section_attributes)?;
+            if !section.docs.is_empty() {
+                let docs_el = create_el("div.layout-docs", vec![], None)?;
+                docs_el.set_inner_html(&markdown_to_html(&section.docs.join("\n")));
+                section_layout.element().append_child(&docs_el)?;
+            }
+
+            for field in section.fields.iter() {
+                if fields.iter().any(|f| f.name == field.name) {
+                    return Err(error!(
+                        "LayoutDescriptor: duplicate field name `{}`",
+                        field.name
+                    ));
+                }
+                fields.push(Self::create_field(&section_layout, field)?);
+            }
+        }
+
+        let footer = FormFooter::new(&layout, &Attributes::new(), &Docs::new())?;
+        if let Some(submit) = &descriptor.submit {
+            footer.set_submit_btn_text(i18n(submit))?;
+        }
+        layout.append_child(&footer.element(), &Attributes::new(), &Docs::new())?;
+
+        let form = Self {
+            layout,
+            fields: Arc::new(fields),
+            footer,
+            on_submit_cb: Arc::new(Mutex::new(None)),
+        };
+        form.init()?;
+        Ok(form)
+    }
+
+    fn create_field(layout: &ElementLayout, field: &FieldDescriptor) -> Result<DescriptorField> {
+        let control = FieldControl::create(layout, field)?;
+        let element = control.element();
+
+        let mut layout_attributes = Attributes::new();
+        if let Some(label) = &field.label {
+            layout_attributes.insert("title".to_string(), i18n(label));
+        }
+        // runtime docs are not `'static`, the info slot is filled in below
+        element.set_attribute("docs", "consume")?;
+        layout.append_child(&element, &layout_attributes, &Docs::new())?;
+        if !field.docs.is_empty() {
+            if let Some(container) = element.parent_element() {
+                let form_control = FormControl { element: container };
+                form_control.set_info(&markdown_to_html(&field.docs.join("\n")))?;
+            }
+        }
+
+        let rules = &field.rules;
+        let mut list = Vec::new();
+        if rules.required {
+            list.push(Rule::Required);
+        }
+        if let Some(len) = rules.min_len {
+            list.push(Rule::MinLen(len));
+        }
+        if let Some(len) = rules.max_len {
+            list.push(Rule::MaxLen(len));
+        }
+        if let Some(min) = rules.min {
+            list.push(Rule::Min(min));
+        }
+        if let Some(max) = rules.max {
+            list.push(Rule::Max(max));
+        }
+        if rules.email {
+            list.push(Rule::Email);
+        }
+        // `Rule::Pattern` only holds static patterns
+        let pattern = match &rules.pattern {
+            Some(pattern) => Some(Regex::new(pattern).map_err(|err| {
+                error!(
+                    "LayoutDescriptor: invalid pattern `{}` of field `{}`: {}",
+                    pattern, field.name, err
+                )
+            })?),
+            None => None,
+        };
+
+        Ok(DescriptorField {
+            name: field.name.clone(),
+            control,
+            rules: list,
+            pattern,
+        })
+    }
+
+    fn init(&self) -> Result<()> {
+        // the footer is owned by the form, which is only referenced weakly
+        let fields = Arc::downgrade(&self.fields);
+        let on_submit_cb = Arc::downgrade(&self.on_submit_cb);
+        self.footer
+            .on_submit_click(Box::new(move |_| -> Result<()> {
+                let (fields, on_submit_cb) = match (fields.upgrade(), on_submit_cb.upgrade()) {
+                    (Some(fields), Some(on_submit_cb)) => (fields, on_submit_cb),
+                    _ => return Ok(()),
+                };
+                if !validate_fields(&fields)? {
+                    return Ok(());
+                }
+                let mut data = FormData::new(None);
+                collect_fields(&fields, &mut data, true);
+                // called outside of the lock, the callback may replace itself
+                let cb = on_submit_cb.lock()?.take();
+                if let Some(mut cb) = cb {
+                    let result = cb(data);
+                    on_submit_cb.lock()?.get_or_insert(cb);
+                    result?;
+                }
+                Ok(())
+            }))
+    }
+
+    pub fn layout(&self) -> ElementLayout {
+        self.layout.clone()
+    }
+
+    pub fn footer(&self) -> FormFooter {
+        self.footer.clone()
+    }
+
+    /// Current control values
+    pub fn data(&self) -> Result<FormData> {
+        self.form_data()
+    }
+
+    pub fn value(&self, name: &str) -> Option<FormDataValue> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.control.value())
+    }
+
+    /// Called with the form data once the submit button is clicked and the form is valid
+    pub fn on_submit(&self, callback: CallbackFn<FormData>) -> Result<()> {
+        *self.on_submit_cb.lock()? = Some(callback);
+        Ok(())
+    }
+}
+
+fn validate_fields(fields: &[DescriptorField]) -> Result<bool> {
+    let mut valid = true;
+    for field in fields.iter() {
+        valid &= field.validate()?;
+    }
+    Ok(valid)
+}
+
+fn collect_fields(fields: &[DescriptorField], data: &mut FormData, sensitive: bool) {
+    for field in fields.iter() {
+        if sensitive || !field.control.is_sensitive() {
+            data.add(&field.name, field.control.value());
+        }
+    }
+}
+
+impl DefaultFunctions for DescriptorForm {
+    fn validate(&self) -> Result<bool> {
+        validate_fields(&self.fields)
+    }
+
+    fn load_form_data(&self, data: &FormData) -> Result<()> {
+        for field in self.fields.iter() {
+            if let Some(value) = data.values.get(&field.name) {
+                field.control.load(&field.name, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn collect_form_data(&self, data: &mut FormData, sensitive: bool) -> Result<()> {
+        collect_fields(&self.fields, data, sensitive);
+        Ok(())
+    }
+}
+
+impl Elemental for DescriptorForm {
+    fn element(&self) -> Element {
+        self.layout.element()
+    }
+}
@@ -57,6 +57,7 @@ pub enum Button {
     Start,
     Stop,
     Discard,
+    Stay,
     Yes,
     No,
     GotIt,
@@ -84,6 +85,7 @@ impl FromStr for Button {
             "Start" => Self::Start,
             "Stop" => Self::Stop,
             "Discard" => Self::Discard,
+            "Stay" => Self::Stay,
             "Yes" => Self::Yes,
             "No" => Self::No,
             "Got It" => Self::GotIt,
@@ -116,6 +118,7 @@ impl Button {
             Self::Start => ("Start", None),
             Self::Stop => ("Stop", None),
             Self::Discard => ("Discard", None),
+            Self::Stay => ("Stay", None),
             Self::Yes => ("Yes", None),
             Self::No => ("No", None),
             Self::GotIt => ("Got It", None),
@@ -443,7 +446,12 @@ impl Dialog {
         Ok(self)
     }
 
+    /// Closes the dialog.  A dialog dismissed by the close icon or the mask
+    /// releases its callback; the callback is kept while it closes the dialog.
     pub fn close(self) -> Result<()> {
+        if let Ok(mut callback) = self.callback.try_lock() {
+            *callback = Box::new(|_, _| Ok(()));
+        }
         self.hide()?.remove_from_list()?;
         Ok(())
     }
@@ -543,6 +551,59 @@ pub async fn async_dialog_with_html(title: &str, msg: Html) -> Result<Button> {
     Ok(btn)
 }
 
+/// Asks the user to choose between the `yes` and `no` buttons.  Returns
+/// `None` if the dialog is dismissed without choosing (see [`Dialog::close`]).
+pub async fn confirm(title: &str, message: &str, yes: Button, no: Button) -> Result<Option<bool>> {
+    let (sender, receiver) = oneshot();
+    let (name, _) = yes.name_and_class();
+    let _dialog = Dialog::new_with_btns(
+        &[no.with_class(ButtonClass::Warning)],
+        &[] as &[Button],
+        &[yes.with_class(ButtonClass::Primary)],
+    )?
+    .set_title(title)?
+    .set_msg(message)?
+    .with_callback(Box::new(move |dialog, btn| {
+        dialog.close()?;
+        sender
+            .try_send(btn)
+            .map_err(|e| Error::DialogError(e.to_string()))?;
+        Ok(())
+    }))?
+    .show()?;
+    match receiver.recv().await {
+        Ok(btn) => Ok(Some(btn.name_and_class().0 == name)),
+        // the sender is dropped along with the callback of a dismissed dialog
+        Err(_) => Ok(None),
+    }
+}
+
+/// Asks the user whether unsaved changes can be discarded.
+/// Returns `true` if the user chooses to discard the changes.
+pub async fn confirm_unsaved_changes() -> Result<bool> {
+    let discard = confirm(
+        &i18n("Unsaved changes"),
+        &i18n("You have unsaved changes. Do you want to discard them?"),
+        Button::Discard,
+        Button::Stay,
+    )
+    .await?;
+    Ok(discard.unwrap_or(false))
+}
+
+/// Asks the user whether a saved form draft should be restored.
+/// Returns `Some(false)` if the user chooses to discard the draft
+/// and `None` if the dialog is dismissed, keeping the draft.
+pub async fn confirm_restore_draft() -> Result<Option<bool>> {
+    confirm(
+        &i18n("Saved draft"),
+        &i18n("An unsaved draft of this form is available. Do you want to restore it?"),
+        Button::Custom("Restore".to_string()),
+        Button::Discard,
+    )
+    .await
+}
+
 pub fn show_dialog(title: &str, msg: &str) -> Result<Dialog> {
     let dialog = Dialog::new()?.set_title(title)?.set_msg(msg)?.show()?;
     Ok(dialog)
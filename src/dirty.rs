@@ -0,0 +1,189 @@
+//!
+//! Tracking of unsaved changes in layouts (see [`DefaultFunctions::is_dirty()`])
+//!
+
+use crate::layout::{DefaultFunctions, Elemental};
+use crate::prelude::*;
+use crate::result::Result;
+use std::collections::BTreeSet;
+use workflow_wasm::callback::CallbackMap;
+use workflow_wasm::prelude::callback;
+
+struct DirtyTrackerInner {
+    initial: FormData,
+    dirty: bool,
+}
+
+/// Compares the control values of a form against a snapshot taken
+/// when the form was bound (or last saved, see [`DirtyTracker::snapshot()`]).
+/// The tracker does not own the form; methods comparing values take it as `source`.
+#[derive(Clone)]
+pub struct DirtyTracker {
+    inner: Arc<Mutex<DirtyTrackerInner>>,
+    change_cbs: Arc<Mutex<Vec<CallbackFn<bool>>>>,
+    callbacks: CallbackMap,
+}
+
+unsafe impl Send for DirtyTracker {}
+unsafe impl Sync for DirtyTracker {}
+
+impl Default for DirtyTracker {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(DirtyTrackerInner {
+                initial: FormData::new(None),
+                dirty: false,
+            })),
+            change_cbs: Arc::new(Mutex::new(Vec::new())),
+            callbacks: CallbackMap::new(),
+        }
+    }
+}
+
+impl DirtyTracker {
+    /// Takes the initial snapshot of the `form` root and re-evaluates the
+    /// dirty state each time one of its controls changes.  The form is held
+    /// weakly; changes emitted while it is locked are skipped, so code setting
+    /// values under the lock calls [`DirtyTracker::refresh()`] itself.
+    pub fn bind<F>(&self, form: &Arc<Mutex<F>>) -> Result<()>
+    where
+        F: DefaultFunctions + Elemental + 'static,
+    {
+        let element = {
+            let locked = form.lock()?;
+            self.snapshot(&*locked)?;
+            locked.element()
+        };
+
+        let this = self.clone();
+        let source = Arc::downgrade(form);
+        let callback = callback!(move |_event: web_sys::Event| -> Result<()> {
+            let source = match source.upgrade() {
+                Some(source) => source,
+                None => return Ok(()),
+            };
+            let locked = match source.try_lock() {
+                Ok(locked) => locked,
+                Err(_) => return Ok(()),
+            };
+            this.refresh(&*locked)
+        });
+        for event in ["input", "change", "changed"] {
+            element.add_event_listener_with_callback(event, callback.as_ref())?;
+        }
+        self.callbacks.retain(callback)?;
+        Ok(())
+    }
+
+    fn inner(&self) -> Result<std::sync::MutexGuard<DirtyTrackerInner>> {
+        Ok(self.inner.lock()?)
+    }
+
+    /// Uses the current values of `source` as the clean state, i.e. after loading or saving
+    pub fn snapshot<S>(&self, source: &S) -> Result<()>
+    where
+        S: DefaultFunctions + ?Sized,
+    {
+        self.inner()?.initial = source.form_data()?;
+        self.refresh(source)
+    }
+
+    /// Names of the controls of `source` whose value differs from the snapshot
+    pub fn dirty_fields<S>(&self, source: &S) -> Result<Vec<String>>
+    where
+        S: DefaultFunctions + ?Sized,
+    {
+        let current = source.form_data()?;
+        Ok(changed_fields(&self.inner()?.initial, &current))
+    }
+
+    /// Dirty state as of the last [`DirtyTracker::refresh()`]
+    pub fn is_dirty(&self) -> Result<bool> {
+        Ok(self.inner()?.dirty)
+    }
+
+    /// Restores the control values of the snapshot in `source`
+    pub fn reset<S>(&self, source: &S) -> Result<()>
+    where
+        S: DefaultFunctions + ?Sized,
+    {
+        let initial = self.inner()?.initial.clone();
+        source.load_form_data(&initial)?;
+        self.refresh(source)
+    }
+
+    /// Re-evaluates the dirty state, notifying the change callbacks if it has changed.
+    /// Needed after values are set programmatically, as that does not emit change events.
+    pub fn refresh<S>(&self, source: &S) -> Result<()>
+    where
+        S: DefaultFunctions + ?Sized,
+    {
+        let dirty = !self.dirty_fields(source)?.is_empty();
+        {
+            let mut inner = self.inner()?;
+            if inner.dirty == dirty {
+                return Ok(());
+            }
+            inner.dirty = dirty;
+        }
+        for cb in self.change_cbs.lock()?.iter_mut() {
+            cb(dirty)?;
+        }
+        Ok(())
+    }
+
+    /// Adds a callback receiving the dirty state each time it changes
+    pub fn on_change(&self, callback: CallbackFn<bool>) -> Result<()> {
+        self.change_cbs.lock()?.push(callback);
+        Ok(())
+    }
+}
+
+/// Names of the values that differ between `initial` and `current`,
+/// including values present in only one of them
+fn changed_fields(initial: &FormData, current: &FormData) -> Vec<String> {
+    let names: BTreeSet<&String> = initial.values.keys().chain(current.values.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| initial.values.get(*name) != current.values.get(*name))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn unchanged_values_are_clean() {
+        let mut initial = FormData::new(None);
+        initial.add_string("name", "value".into());
+        initial.add("enabled", FormDataValue::Bool(true));
+        assert!(changed_fields(&initial, &initial.clone()).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn changed_values_are_dirty() {
+        let mut initial = FormData::new(None);
+        initial.add_string("name", "value".into());
+        initial.add("enabled", FormDataValue::Bool(true));
+        let mut current = initial.clone();
+        current.add_string("name", "changed".into());
+        assert_eq!(changed_fields(&initial, &current), vec!["name".to_string()]);
+    }
+
+    #[wasm_bindgen_test]
+    fn added_and_removed_values_are_dirty() {
+        let mut initial = FormData::new(None);
+        initial.add_string("removed", "value".into());
+        initial.add_string("kept", "value".into());
+        let mut current = FormData::new(None);
+        current.add_string("kept", "value".into());
+        current.add_string("added", "value".into());
+        assert_eq!(
+            changed_fields(&initial, &current),
+            vec!["added".to_string(), "removed".to_string()]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//!
+//! Form drafts persisted to the local storage (see `#[form(draft = "key")]`)
+//!
+
+use crate::dialog::confirm_restore_draft;
+use crate::layout::{DefaultFunctions, Elemental};
+use crate::prelude::*;
+use crate::result::Result;
+use crate::task::FunctionDebounce;
+use crate::utils::local_storage;
+use serde::{Deserialize, Serialize};
+use workflow_wasm::callback::CallbackMap;
+use workflow_wasm::prelude::callback;
+
+#[derive(Serialize, Deserialize)]
+struct DraftRecord {
+    /// milliseconds since epoch
+    saved: f64,
+    data: FormData,
+}
+
+/// Keeps the values of a form in the local storage while it is being
+/// edited, so they can be restored when the form is created again.
+/// Sensitive controls (`Mnemonic`, password inputs) are never stored.
+#[derive(Clone)]
+pub struct Draft {
+    key: String,
+    /// maximum age in seconds
+    max_age: Option<u64>,
+    delay: u32,
+    restoring: Arc<Mutex<bool>>,
+    /// set by [`Draft::finish()`] until the draft is bound again
+    finished: Arc<Mutex<bool>>,
+    debounce: Arc<Mutex<Option<FunctionDebounce>>>,
+    callbacks: CallbackMap,
+}
+
+unsafe impl Send for Draft {}
+unsafe impl Sync for Draft {}
+
+impl Draft {
+    /// Drafts expire after a week unless specified otherwise
+    pub const DEFAULT_MAX_AGE: u64 = 7 * 24 * 60 * 60;
+    pub const DEFAULT_DELAY: u32 = 1000;
+
+    pub fn new(key: &str, max_age: Option<u64>) -> Self {
+        Self {
+            key: key.to_string(),
+            max_age,
+            delay: Self::DEFAULT_DELAY,
+            restoring: Arc::new(Mutex::new(false)),
+            finished: Arc::new(Mutex::new(false)),
+            debounce: Arc::new(Mutex::new(None)),
+            callbacks: CallbackMap::new(),
+        }
+    }
+
+    /// Debounce delay (in milliseconds) between a change and saving the draft
+    pub fn with_delay(mut self, delay: u32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn storage_key(&self) -> String {
+        format!("workflow-ux-draft:{}", self.key)
+    }
+
+    pub fn save(&self, data: &FormData) -> Result<()> {
+        let record = DraftRecord {
+            saved: js_sys::Date::now(),
+            data: data.clone(),
+        };
+        local_storage().set_item(&self.storage_key(), &serde_json::to_string(&record)?)?;
+        Ok(())
+    }
+
+    /// Stored draft, if any; expired or unreadable drafts are removed
+    pub fn load(&self) -> Result<Option<FormData>> {
+        let json = match local_storage().get_item(&self.storage_key())? {
+            Some(json) => json,
+            None => return Ok(None),
+        };
+        let record = match serde_json::from_str::<DraftRecord>(&json) {
+            Ok(record) => record,
+            Err(err) => {
+                log_warning!("Draft: discarding unreadable draft `{}`: {}", self.key, err);
+                self.clear()?;
+                return Ok(None);
+            }
+        };
+        if is_expired(record.saved, js_sys::Date::now(), self.max_age) {
+            self.clear()?;
+            return Ok(None);
+        }
+        Ok(Some(record.data))
+    }
+
+    /// Removes the stored draft, including a pending save
+    pub fn clear(&self) -> Result<()> {
+        if let Some(debounce) = self.debounce.lock()?.as_ref() {
+            debounce.cancel()?;
+        }
+        local_storage().remove_item(&self.storage_key())?;
+        Ok(())
+    }
+
+    /// Removes the stored draft once the form has been submitted; further
+    /// changes are not saved until the form is bound (loaded) again
+    pub fn finish(&self) -> Result<()> {
+        *self.finished.lock()? = true;
+        self.clear()
+    }
+
+    /// Saves the draft of `form` on each change and, if a draft was stored
+    /// earlier, offers to restore it. Changes are not saved until the offer
+    /// is answered. The form (owning the draft) is held weakly.
+    pub fn bind<F>(&self, form: &Arc<Mutex<F>>) -> Result<()>
+    where
+        F: DefaultFunctions + Elemental + 'static,
+    {
+        *self.finished.lock()? = false;
+        let draft = self.clone();
+        let target = Arc::downgrade(form);
+        *self.debounce.lock()? = Some(FunctionDebounce::new(
+            self.delay,
+            Box::new(move || {
+                if *draft.restoring.lock()? || *draft.finished.lock()? {
+                    return Ok(());
+                }
+                let target = match target.upgrade() {
+                    Some(target) => target,
+                    None => return Ok(()),
+                };
+                let mut data = FormData::new(None);
+                target.lock()?.collect_form_data(&mut data, false)?;
+                draft.save(&data)
+            }),
+        ));
+
+        let debounce = self.debounce.clone();
+        let callback = callback!(move |_event: web_sys::Event| -> Result<()> {
+            if let Some(debounce) = debounce.lock()?.as_ref() {
+                debounce.execute()?;
+            }
+            Ok(())
+        });
+        let element = form.lock()?.element();
+        for event in ["input", "change", "changed"] {
+            element.add_event_listener_with_callback(event, callback.as_ref())?;
+        }
+        self.callbacks.retain(callback)?;
+
+        if let Some(data) = self.load()? {
+            self.offer_restore(form, data)?;
+        }
+
+        Ok(())
+    }
+
+    fn offer_restore<F>(&self, form: &Arc<Mutex<F>>, data: FormData) -> Result<()>
+    where
+        F: DefaultFunctions + Elemental + 'static,
+    {
+        *self.restoring.lock()? = true;
+        let draft = self.clone();
+        let form = Arc::downgrade(form);
+        workflow_core::task::wasm::dispatch(async move {
+            let result = match confirm_restore_draft().await {
+                Ok(Some(true)) => match form.upgrade() {
+                    Some(form) => restore(&form, &data),
+                    None => Ok(()),
+                },
+                Ok(Some(false)) => draft.clear(),
+                // dismissed, the draft is offered again next time
+                Ok(None) => Ok(()),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                log_error!("Draft: unable to restore draft `{}`: {}", draft.key, err);
+            }
+            if let Ok(mut restoring) = draft.restoring.lock() {
+                *restoring = false;
+            }
+        });
+        Ok(())
+    }
+}
+
+fn restore<F: DefaultFunctions>(form: &Mutex<F>, data: &FormData) -> Result<()> {
+    let form = form.lock()?;
+    form.load_form_data(data)?;
+    match form.dirty_tracker() {
+        // restored values are unsaved changes
+        Some(tracker) => tracker.refresh(&*form),
+        None => Ok(()),
+    }
+}
+
+/// Whether a draft saved at `saved` (milliseconds since epoch)
+/// is older than `max_age` seconds at `now`
+fn is_expired(saved: f64, now: f64, max_age: Option<u64>) -> bool {
+    match max_age {
+        Some(max_age) => now - saved > max_age as f64 * 1000.0,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn expiry() {
+        let saved = 1_000_000.0;
+        assert!(!is_expired(saved, saved + 60_000.0, Some(60)));
+        assert!(is_expired(saved, saved + 60_001.0, Some(60)));
+        assert!(!is_expired(saved, saved + 1e15, None));
+    }
+
+    #[wasm_bindgen_test]
+    fn expiry_does_not_overflow() {
+        assert!(!is_expired(0.0, 1e15, Some(u64::MAX)));
+        assert!(!is_expired(
+            0.0,
+            1e15,
+            Some(Draft::DEFAULT_MAX_AGE * 1_000_000)
+        ));
+    }
+}
@@ -78,6 +78,21 @@ pub enum Error {
     #[error("Dialog error: {0}")]
     DialogError(String),
 
+    #[error("View eviction cancelled")]
+    EvictionCancelled,
+
+    #[error("Overlay cancelled")]
+    OverlayCancelled,
+
+    #[error("FormData: missing field `{0}`")]
+    FormDataMissingField(String),
+
+    #[error("FormData: field `{0}` is expected to be {1}")]
+    FormDataFieldType(String, String),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
     #[error("Downcast error: {0}")]
     Downcast(String),
 
@@ -5,6 +5,7 @@ use borsh::{
 use downcast::{downcast_sync, AnySync};
 use paste::paste;
 //use workflow_log::log_trace;
+use crate::controls::prelude::{Checkbox, Input, Mnemonic, Radio, Select, Textarea};
 use crate::{
     async_trait_without_send,
     attributes::Attributes,
@@ -15,16 +16,23 @@ use crate::{
     //document,
     //module::ModuleInterface
     form_footer::FormFooter,
-    layout::{ElementLayout, ElementLayoutStyle, Elemental},
+    icon::Icon,
+    layout::{DefaultFunctions, ElementLayout, ElementLayoutStyle, Elemental},
     prelude::{i18n, CallbackFn},
     result::Result,
+    stepper::{Step, StepState, Stepper},
+    validation::validate_layout,
 };
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt::Display;
 use std::{
     collections::BTreeMap,
     str,
     sync::{Arc, Mutex},
 };
+use wasm_bindgen::JsValue;
 use web_sys::Element;
+use workflow_core::enums::EnumTrait;
 
 pub struct Category {
     pub key: String,
@@ -49,7 +57,11 @@ where
     }
 }
 
-#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+/// Value stored under a [`FormData`] key.  New variants may be added
+/// (i.e. `Json`), matches must include a wildcard arm.
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Deserialize)]
+#[serde(try_from = "FormDataValueJson")]
+#[non_exhaustive]
 pub enum FormDataValue {
     String(String),
     Bool(bool),
@@ -65,6 +77,104 @@ pub enum FormDataValue {
     //Usize(usize)
     List(Vec<String>),
     Object(Vec<u8>),
+    Json(String),
+}
+
+/// Stable JSON representation of [`FormDataValue`], i.e. `{"type":"u8","value":1}`.
+/// `u64`/`u128` values are encoded as decimal strings (JavaScript numbers
+/// can not hold them), Borsh objects as hex strings and JSON objects as-is.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+enum FormDataValueJson {
+    String(String),
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(String),
+    U128(String),
+    F32(f32),
+    F64(f64),
+    List(Vec<String>),
+    Object(String),
+    Json(serde_json::Value),
+}
+
+impl FormDataValue {
+    /// Text representation of scalar values, as used by text-based controls
+    pub fn as_string(&self) -> Option<String> {
+        match self {
+            Self::String(v) => Some(v.clone()),
+            Self::Bool(v) => Some(v.to_string()),
+            Self::U8(v) => Some(v.to_string()),
+            Self::U16(v) => Some(v.to_string()),
+            Self::U32(v) => Some(v.to_string()),
+            Self::U64(v) => Some(v.to_string()),
+            Self::U128(v) => Some(v.to_string()),
+            Self::F32(v) => Some(v.to_string()),
+            Self::F64(v) => Some(v.to_string()),
+            Self::List(_) | Self::Object(_) | Self::Json(_) => None,
+        }
+    }
+}
+
+impl Serialize for FormDataValue {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        FormDataValueJson::try_from(self.clone())
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl TryFrom<FormDataValue> for FormDataValueJson {
+    type Error = String;
+    fn try_from(value: FormDataValue) -> std::result::Result<Self, String> {
+        let value = match value {
+            FormDataValue::String(v) => Self::String(v),
+            FormDataValue::Bool(v) => Self::Bool(v),
+            FormDataValue::U8(v) => Self::U8(v),
+            FormDataValue::U16(v) => Self::U16(v),
+            FormDataValue::U32(v) => Self::U32(v),
+            FormDataValue::U64(v) => Self::U64(v.to_string()),
+            FormDataValue::U128(v) => Self::U128(v.to_string()),
+            FormDataValue::F32(v) => Self::F32(v),
+            FormDataValue::F64(v) => Self::F64(v),
+            FormDataValue::List(v) => Self::List(v),
+            FormDataValue::Object(v) => Self::Object(hex::encode(v)),
+            FormDataValue::Json(v) => {
+                Self::Json(serde_json::from_str(&v).map_err(|err| format!("json: {err}"))?)
+            }
+        };
+        Ok(value)
+    }
+}
+
+impl TryFrom<FormDataValueJson> for FormDataValue {
+    type Error = String;
+    fn try_from(value: FormDataValueJson) -> std::result::Result<Self, String> {
+        let value = match value {
+            FormDataValueJson::String(v) => Self::String(v),
+            FormDataValueJson::Bool(v) => Self::Bool(v),
+            FormDataValueJson::U8(v) => Self::U8(v),
+            FormDataValueJson::U16(v) => Self::U16(v),
+            FormDataValueJson::U32(v) => Self::U32(v),
+            FormDataValueJson::U64(v) => Self::U64(v.parse().map_err(|err| format!("u64: {err}"))?),
+            FormDataValueJson::U128(v) => {
+                Self::U128(v.parse().map_err(|err| format!("u128: {err}"))?)
+            }
+            FormDataValueJson::F32(v) => Self::F32(v),
+            FormDataValueJson::F64(v) => Self::F64(v),
+            FormDataValueJson::List(v) => Self::List(v),
+            FormDataValueJson::Object(v) => {
+                Self::Object(hex::decode(v).map_err(|err| format!("object: {err}"))?)
+            }
+            FormDataValueJson::Json(v) => Self::Json(v.to_string()),
+        };
+        Ok(value)
+    }
 }
 
 macro_rules! define_fields {
@@ -92,7 +202,7 @@ macro_rules! define_fields {
     }
 }
 
-#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub struct FormData {
     pub id: Option<String>,
     pub values: BTreeMap<String, FormDataValue>,
@@ -139,6 +249,38 @@ impl FormData {
 
         Ok(None)
     }
+    pub fn add_object_json(&mut self, name: &str, obj: impl Serialize) -> Result<()> {
+        let json = serde_json::to_string(&obj)?;
+        self.values
+            .insert(name.to_string(), FormDataValue::Json(json));
+        Ok(())
+    }
+
+    pub fn get_object_json<D: DeserializeOwned>(&self, name: &str) -> Result<Option<D>> {
+        if let Some(FormDataValue::Json(json)) = self.values.get(name) {
+            return Ok(Some(serde_json::from_str(json)?));
+        }
+
+        Ok(None)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_js_value(&self) -> Result<JsValue> {
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        Ok(Serialize::serialize(self, &serializer)?)
+    }
+
+    pub fn from_js_value(value: JsValue) -> Result<Self> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+
     pub fn add_string(&mut self, name: &str, value: String) {
         self.values
             .insert(name.to_string(), FormDataValue::String(value));
@@ -159,6 +301,30 @@ impl FormData {
 
     define_fields!(U8 U16 U32 U64 U128 F32 F64 Bool);
 
+    pub fn add_enum<E: EnumTrait<E>>(&mut self, name: &str, value: E) {
+        self.add_string(name, value.as_str().to_string());
+    }
+
+    pub fn get_enum<E: EnumTrait<E>>(&self, name: &str) -> Result<Option<E>> {
+        match self.values.get(name) {
+            Some(FormDataValue::String(value)) => match <E as EnumTrait<E>>::from_str(value) {
+                Some(value) => Ok(Some(value)),
+                None => {
+                    let variants: Vec<&str> = E::list().iter().map(|e| e.as_str()).collect();
+                    Err(Error::FormDataFieldType(
+                        name.to_string(),
+                        format!("one of {}", variants.join(", ")),
+                    ))
+                }
+            },
+            Some(_) => Err(Error::FormDataFieldType(
+                name.to_string(),
+                "a string".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+
     pub fn empty() -> Self {
         Self {
             id: None,
@@ -167,6 +333,193 @@ impl FormData {
     }
 }
 
+/// Values that can be stored under a [`FormData`] key;
+/// used by the `#[derive(FormModel)]` macro.
+pub trait FormDataField: Sized {
+    fn add_to(self, data: &mut FormData, name: &str) -> Result<()>;
+    fn get_from(data: &FormData, name: &str) -> Result<Self>;
+}
+
+macro_rules! form_data_fields {
+    ($($ident:ident)+)=>{
+        paste!{
+            $(impl FormDataField for [<$ident:lower>] {
+                fn add_to(self, data: &mut FormData, name: &str) -> Result<()> {
+                    data.add(name, FormDataValue::$ident(self));
+                    Ok(())
+                }
+                fn get_from(data: &FormData, name: &str) -> Result<Self> {
+                    match data.values.get(name) {
+                        Some(FormDataValue::$ident(value)) => Ok(value.clone()),
+                        Some(_) => Err(Error::FormDataFieldType(
+                            name.to_string(),
+                            format!("`{}`", stringify!([<$ident:lower>])),
+                        )),
+                        None => Err(Error::FormDataMissingField(name.to_string())),
+                    }
+                }
+            })+
+        }
+    }
+}
+
+form_data_fields!(U8 U16 U32 U64 U128 F32 F64 Bool);
+
+impl FormDataField for String {
+    fn add_to(self, data: &mut FormData, name: &str) -> Result<()> {
+        data.add_string(name, self);
+        Ok(())
+    }
+    fn get_from(data: &FormData, name: &str) -> Result<Self> {
+        match data.values.get(name) {
+            Some(FormDataValue::String(value)) => Ok(value.clone()),
+            Some(_) => Err(Error::FormDataFieldType(
+                name.to_string(),
+                "a string".into(),
+            )),
+            None => Err(Error::FormDataMissingField(name.to_string())),
+        }
+    }
+}
+
+impl FormDataField for Vec<String> {
+    fn add_to(self, data: &mut FormData, name: &str) -> Result<()> {
+        data.add_list(name, self);
+        Ok(())
+    }
+    fn get_from(data: &FormData, name: &str) -> Result<Self> {
+        match data.values.get(name) {
+            Some(FormDataValue::List(list)) => Ok(list.clone()),
+            Some(_) => Err(Error::FormDataFieldType(name.to_string(), "a list".into())),
+            None => Err(Error::FormDataMissingField(name.to_string())),
+        }
+    }
+}
+
+/// Nested form data is stored as an object
+impl FormDataField for FormData {
+    fn add_to(self, data: &mut FormData, name: &str) -> Result<()> {
+        data.add_object(name, self)
+    }
+    fn get_from(data: &FormData, name: &str) -> Result<Self> {
+        if let Some(value) = data.get_object(name)? {
+            return Ok(value);
+        }
+        match data.values.get(name) {
+            Some(_) => Err(Error::FormDataFieldType(
+                name.to_string(),
+                "an object".into(),
+            )),
+            None => Err(Error::FormDataMissingField(name.to_string())),
+        }
+    }
+}
+
+/// Missing keys resolve to `None`
+impl<T: FormDataField> FormDataField for Option<T> {
+    fn add_to(self, data: &mut FormData, name: &str) -> Result<()> {
+        match self {
+            Some(value) => value.add_to(data, name),
+            None => Ok(()),
+        }
+    }
+    fn get_from(data: &FormData, name: &str) -> Result<Self> {
+        if data.values.contains_key(name) {
+            Ok(Some(T::get_from(data, name)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Controls that can be populated from and read into a [`FormDataValue`];
+/// used by the layout macros to load [`FormData`] into a layout.
+pub trait FormValue {
+    fn load_form_value(&self, name: &str, value: &FormDataValue) -> Result<()>;
+    fn form_value(&self) -> FormDataValue;
+    /// Sensitive values (secrets, passwords) are never persisted, i.e. in form drafts
+    fn is_sensitive(&self) -> bool {
+        false
+    }
+}
+
+fn text_form_value(name: &str, value: &FormDataValue) -> Result<String> {
+    value
+        .as_string()
+        .ok_or_else(|| Error::FormDataFieldType(name.to_string(), "a scalar value".into()))
+}
+
+impl FormValue for Input {
+    fn load_form_value(&self, name: &str, value: &FormDataValue) -> Result<()> {
+        self.set_value(text_form_value(name, value)?)
+    }
+    fn form_value(&self) -> FormDataValue {
+        FormDataValue::String(self.value())
+    }
+    fn is_sensitive(&self) -> bool {
+        matches!(self.attributes.get("type"), Some(kind) if kind == "password")
+    }
+}
+
+impl FormValue for Textarea {
+    fn load_form_value(&self, name: &str, value: &FormDataValue) -> Result<()> {
+        self.set_value(text_form_value(name, value)?)
+    }
+    fn form_value(&self) -> FormDataValue {
+        FormDataValue::String(self.value())
+    }
+}
+
+impl FormValue for Mnemonic {
+    fn load_form_value(&self, name: &str, value: &FormDataValue) -> Result<()> {
+        self.set_value(text_form_value(name, value)?)
+    }
+    fn form_value(&self) -> FormDataValue {
+        FormDataValue::String(self.value())
+    }
+    fn is_sensitive(&self) -> bool {
+        true
+    }
+}
+
+impl<E: EnumTrait<E>> FormValue for Select<E> {
+    fn load_form_value(&self, name: &str, value: &FormDataValue) -> Result<()> {
+        let value = value
+            .as_string()
+            .ok_or_else(|| Error::FormDataFieldType(name.to_string(), "a string".into()))?;
+        self.set_value(value)
+    }
+    fn form_value(&self) -> FormDataValue {
+        FormDataValue::String(self.value())
+    }
+}
+
+impl<E: EnumTrait<E> + 'static + Display> FormValue for Radio<E> {
+    fn load_form_value(&self, name: &str, value: &FormDataValue) -> Result<()> {
+        let value = value
+            .as_string()
+            .ok_or_else(|| Error::FormDataFieldType(name.to_string(), "a string".into()))?;
+        // clones share the element and the value
+        self.clone().set_value(value)
+    }
+    fn form_value(&self) -> FormDataValue {
+        FormDataValue::String(self.value())
+    }
+}
+
+impl FormValue for Checkbox {
+    fn load_form_value(&self, name: &str, value: &FormDataValue) -> Result<()> {
+        match value {
+            FormDataValue::Bool(checked) => self.set_value(*checked),
+            FormDataValue::String(checked) => self.set_value(checked == "true"),
+            _ => Err(Error::FormDataFieldType(name.to_string(), "a bool".into())),
+        }
+    }
+    fn form_value(&self) -> FormDataValue {
+        FormDataValue::Bool(self.value())
+    }
+}
+
 #[async_trait_without_send]
 pub trait FormHandler {
     async fn load(&self) -> Result<()>;
@@ -176,6 +529,41 @@ pub trait FormHandler {
 #[async_trait_without_send]
 pub trait FormStage: Elemental + AnySync {
     async fn serialize(&self) -> Result<FormData>;
+    /// Generated functions of the stage layout, used to validate and load
+    /// the stage; `#[stage]` layouts return `Some(self)`.
+    fn layout(&self) -> Option<&dyn DefaultFunctions> {
+        None
+    }
+    /// Populates the stage from existing data, i.e. when editing a record
+    async fn load(&self, data: &FormData) -> Result<()> {
+        match self.layout() {
+            Some(layout) => layout.load_form_data(data),
+            None => Ok(()),
+        }
+    }
+    /// Whether the stage is part of the path for the accumulated
+    /// [`FormStages::data()`]; inactive stages are skipped.
+    fn is_active(&self, _data: &FormData) -> bool {
+        true
+    }
+    /// Name identifying the stage as the target of [`FormStage::next_stage`],
+    /// like the field names of a `#[stage]` layout.
+    fn name(&self) -> Option<&'static str> {
+        None
+    }
+    /// Name of the stage following this one, allowing the path to branch
+    /// (see [`FormStage::name`]).  `None` continues with the next active stage.
+    fn next_stage(&self, _data: &FormData) -> Option<&'static str> {
+        None
+    }
+    /// Title displayed by the [`Stepper`], the `title` attribute of the stage layout by default
+    fn title(&self) -> Option<String> {
+        self.layout().and_then(|layout| layout.stage_title())
+    }
+    /// Icon displayed by the [`Stepper`], the `icon` attribute of the stage layout by default
+    fn icon(&self) -> Option<Icon> {
+        self.layout().and_then(|layout| layout.stage_icon())
+    }
     async fn activate(&self) -> Result<()>;
     async fn deactivate(&self) -> Result<()>;
 }
@@ -186,9 +574,12 @@ downcast_sync!(dyn FormStage);
 pub struct FormStages {
     layout: ElementLayout,
     index: Arc<Mutex<u8>>,
+    history: Arc<Mutex<Vec<u8>>>,
     pub stages: Arc<Mutex<Vec<Arc<dyn FormStage>>>>,
     pub data: Arc<Mutex<FormData>>,
     pub title: Arc<Mutex<String>>,
+    stepper: Arc<Mutex<Option<Stepper>>>,
+    footer: Arc<Mutex<Option<FormFooter>>>,
     error_cb: Arc<Mutex<Option<CallbackFn<Error>>>>,
 }
 
@@ -211,11 +602,24 @@ impl FormStages {
             layout,
             title: Arc::new(Mutex::new(title)),
             index: Arc::new(Mutex::new(0)),
+            history: Arc::new(Mutex::new(Vec::new())),
             stages: Arc::new(Mutex::new(Vec::new())),
             data: Arc::new(Mutex::new(FormData::new(None))),
+            stepper: Arc::new(Mutex::new(None)),
+            footer: Arc::new(Mutex::new(None)),
             error_cb: Arc::new(Mutex::new(None)),
         };
 
+        if let Some(stepper) = attributes.get("stepper") {
+            if stepper.as_str() != "false" {
+                let navigable = attributes
+                    .get("navigable")
+                    .map(|navigable| navigable.as_str() != "false")
+                    .unwrap_or(false);
+                layout.enable_stepper(navigable)?;
+            }
+        }
+
         Ok(layout)
     }
 
@@ -224,6 +628,51 @@ impl FormStages {
         Ok(())
     }
 
+    /// Displays a [`Stepper`] with the stages of the effective path.
+    /// If `navigable` is set, clicking a completed stage returns to it.
+    pub fn enable_stepper(&self, navigable: bool) -> Result<()> {
+        let stepper = Stepper::try_new()?;
+        stepper.set_navigable(navigable)?;
+        let this = self.clone();
+        stepper.on_select(Box::new(move |position| -> Result<()> {
+            let index = match this.history.lock()?.get(position) {
+                Some(index) => *index,
+                None => return Ok(()),
+            };
+            let this = this.clone();
+            workflow_core::task::wasm::dispatch(async move {
+                let footer = this.footer.lock().ok().and_then(|footer| footer.clone());
+                if let Err(err) = this.activate_stage(index, footer.as_ref()).await {
+                    this.show_error(err).ok();
+                }
+            });
+            Ok(())
+        }))?;
+
+        let element = self.layout.element();
+        match element.query_selector(".layout-title")? {
+            Some(title) => {
+                title.insert_adjacent_element("afterend", &stepper.element())?;
+            }
+            None => {
+                element.prepend_with_node_1(&stepper.element())?;
+            }
+        }
+        *self.stepper.lock()? = Some(stepper);
+        self.update_title()?;
+        Ok(())
+    }
+
+    pub fn stepper(&self) -> Result<Option<Stepper>> {
+        Ok(self.stepper.lock()?.clone())
+    }
+
+    /// Footer used when a stage is selected from the [`Stepper`]
+    pub fn set_footer(&self, footer: &FormFooter) -> Result<()> {
+        *self.footer.lock()? = Some(footer.clone());
+        Ok(())
+    }
+
     pub fn show(&self, show: bool) -> Result<()> {
         let el = self.layout.element();
         if show {
@@ -233,7 +682,33 @@ impl FormStages {
         }
     }
 
-    pub fn load(&self, _data: FormData) -> Result<()> {
+    /// Loads existing data into the stage layouts (see [`FormStage::layout`]).
+    /// Data serialized by a stage (stored as `stage_<index>`) is handed back
+    /// to that stage, otherwise each stage receives the complete `data`.
+    /// Use [`Self::load_async()`] for stages overriding [`FormStage::load`].
+    pub fn load(&self, data: FormData) -> Result<()> {
+        *self.data.lock()? = data.clone();
+        for (index, stage) in self.stages()?.iter().enumerate() {
+            if let Some(layout) = stage.layout() {
+                match data.get_object::<FormData>(&format!("stage_{index}"))? {
+                    Some(stage_data) => layout.load_form_data(&stage_data)?,
+                    None => layout.load_form_data(&data)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads existing data into the stages via [`FormStage::load`],
+    /// see [`Self::load()`].
+    pub async fn load_async(&self, data: FormData) -> Result<()> {
+        *self.data.lock()? = data.clone();
+        for (index, stage) in self.stages()?.iter().enumerate() {
+            match data.get_object::<FormData>(&format!("stage_{index}"))? {
+                Some(stage_data) => stage.load(&stage_data).await?,
+                None => stage.load(&data).await?,
+            }
+        }
         Ok(())
     }
 
@@ -242,18 +717,55 @@ impl FormStages {
     }
 
     pub fn is_finished(&self) -> Result<bool> {
-        let index = (self.index()? + 1) as usize;
-        let length = self.len()?;
-        Ok(index >= length)
+        Ok(self.next_index(self.index()?)?.is_none())
     }
 
     pub fn is_first(&self) -> Result<bool> {
-        Ok(self.index()? == 0)
+        Ok(self.history.lock()?.is_empty())
     }
 
     pub fn is_last(&self) -> Result<bool> {
-        let index = (self.index()? + 1) as usize;
-        Ok(self.stages()?.len() == index)
+        self.is_finished()
+    }
+
+    /// Index of the stage following `index` on the effective path
+    pub fn next_index(&self, index: u8) -> Result<Option<u8>> {
+        let stages = self.stages()?;
+        let data = self.data()?;
+        let stage = match stages.get(index as usize) {
+            Some(stage) => stage,
+            None => return Ok(None),
+        };
+
+        if let Some(next) = stage.next_stage(&data) {
+            return match stages.iter().position(|stage| stage.name() == Some(next)) {
+                Some(index) => Ok(Some(index as u8)),
+                None => Err(error!("FormStages: unknown next stage `{}`", next)),
+            };
+        }
+
+        let next = stages
+            .iter()
+            .enumerate()
+            .skip(index as usize + 1)
+            .find(|(_, stage)| stage.is_active(&data))
+            .map(|(i, _)| i as u8);
+        Ok(next)
+    }
+
+    /// Stage indexes of the effective path: the visited stages, the current
+    /// stage and the stages that follow it given the accumulated data.
+    pub fn path(&self) -> Result<Vec<u8>> {
+        let mut path = self.history.lock()?.clone();
+        let mut index = Some(self.index()?);
+        while let Some(i) = index {
+            if path.contains(&i) {
+                break;
+            }
+            path.push(i);
+            index = self.next_index(i)?;
+        }
+        Ok(path)
     }
 
     pub fn len(&self) -> Result<usize> {
@@ -313,22 +825,69 @@ impl FormStages {
     }
 
     pub async fn activate_stage(&self, index: u8, footer: Option<&FormFooter>) -> Result<()> {
+        {
+            let mut history = self.history.lock()?;
+            if let Some(position) = history.iter().position(|i| *i == index) {
+                history.truncate(position);
+            }
+        }
         self.set_index(index, footer).await?;
         Ok(())
     }
 
-    pub async fn next(&self, footer: Option<&FormFooter>) -> Result<bool> {
-        if self.is_finished()? {
-            return Ok(false);
+    /// Validates the current stage layout (see [`FormStage::layout`]),
+    /// flagging the stage in the [`Stepper`] if it is invalid.
+    pub async fn validate_stage(&self) -> Result<bool> {
+        let stage = self.stage()?;
+        let valid = match stage.layout() {
+            Some(layout) => validate_layout(layout).await?,
+            None => true,
+        };
+        if let Some(stepper) = self.stepper()? {
+            let position = self.history.lock()?.len();
+            if valid {
+                stepper.clear_error(position)?;
+            } else {
+                stepper.mark_error(position)?;
+            }
         }
+        Ok(valid)
+    }
+
+    /// Advances to the next stage on the effective path.  The stage
+    /// predicates are evaluated over the accumulated [`Self::data()`], so the
+    /// current stage should be validated ([`Self::validate_stage()`]) and
+    /// serialized ([`Self::serialize_stage()`]) beforehand.
+    pub async fn next(&self, footer: Option<&FormFooter>) -> Result<bool> {
+        let index = self.index()?;
+        let next = match self.next_index(index)? {
+            Some(next) => next,
+            None => return Ok(false),
+        };
 
-        self.set_index(self.index()? + 1, footer).await?;
+        self.history.lock()?.push(index);
+        self.set_index(next, footer).await?;
 
         Ok(true)
     }
 
-    async fn set_index(&self, mut index: u8, footer: Option<&FormFooter>) -> Result<()> {
+    /// Returns to the previously visited stage
+    pub async fn previous(&self, footer: Option<&FormFooter>) -> Result<bool> {
+        let previous = self.history.lock()?.pop();
+        match previous {
+            Some(index) => {
+                self.set_index(index, footer).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn set_index(&self, index: u8, footer: Option<&FormFooter>) -> Result<()> {
         let stages = self.stages()?;
+        if index as usize >= stages.len() {
+            return Err(error!("Invalid stage index"));
+        }
         for (i, stage) in stages.iter().enumerate() {
             if i == index as usize {
                 self.element().append_child(&stage.element())?;
@@ -339,12 +898,10 @@ impl FormStages {
         }
 
         if let Some(footer) = footer {
-            let last = stages.len() - 1;
-            if (index as usize) < last {
+            if self.next_index(index)?.is_some() {
                 footer.set_submit_btn_text(i18n("Next"))?;
             } else {
                 footer.set_submit_btn_text(i18n("Submit"))?;
-                index = last as u8;
             }
         }
 
@@ -356,15 +913,45 @@ impl FormStages {
 
     pub fn update_title(&self) -> Result<()> {
         self.render_title(self.title()?)?;
+        self.render_stepper()?;
         Ok(())
     }
 
+    pub fn render_stepper(&self) -> Result<()> {
+        let stepper = match self.stepper()? {
+            Some(stepper) => stepper,
+            None => return Ok(()),
+        };
+        let stages = self.stages()?;
+        let completed = self.history.lock()?.len();
+        let mut steps = Vec::new();
+        for (position, index) in self.path()?.into_iter().enumerate() {
+            let stage = match stages.get(index as usize) {
+                Some(stage) => stage,
+                None => continue,
+            };
+            let title = stage
+                .title()
+                .unwrap_or_else(|| i18n("Step [N]").replace("[N]", &(position + 1).to_string()));
+            let state = if position < completed {
+                StepState::Completed
+            } else if position == completed {
+                StepState::Current
+            } else {
+                StepState::Pending
+            };
+            steps.push(Step::new(title).with_icon(stage.icon()).with_state(state));
+        }
+        stepper.set_steps(steps)
+    }
+
     pub fn render_title<T: AsRef<str>>(&self, title: T) -> Result<()> {
         if let Some(el) = self.layout.element().query_selector(".layout-title")? {
+            let path = self.path()?;
             let title = title
                 .as_ref()
-                .replace("[INDEX]", &format!("{}", self.index()? + 1))
-                .replace("[STEPS]", &format!("{}", self.len()?));
+                .replace("[INDEX]", &format!("{}", self.history.lock()?.len() + 1))
+                .replace("[STEPS]", &format!("{}", path.len()));
             el.set_inner_html(&title)
         }
         Ok(())
@@ -394,3 +981,113 @@ impl From<FormStages> for Element {
         form.layout.element()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn round_trip(value: FormDataValue) -> FormDataValue {
+        let json = serde_json::to_string(&value).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn json_round_trip() {
+        let values = [
+            FormDataValue::String("text".into()),
+            FormDataValue::Bool(true),
+            FormDataValue::U8(u8::MAX),
+            FormDataValue::U16(u16::MAX),
+            FormDataValue::U32(u32::MAX),
+            FormDataValue::U64(u64::MAX),
+            FormDataValue::U128(u128::MAX),
+            FormDataValue::F32(1.5),
+            FormDataValue::F64(-2.25),
+            FormDataValue::List(vec!["a".into(), "b".into()]),
+            FormDataValue::Object(vec![0, 1, 254, 255]),
+            FormDataValue::Json(r#"{"a":[1,2]}"#.into()),
+        ];
+        for value in values {
+            assert_eq!(round_trip(value.clone()), value);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn json_representation() {
+        let json = serde_json::to_value(FormDataValue::U64(u64::MAX)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "u64", "value": u64::MAX.to_string() })
+        );
+        let json = serde_json::to_value(FormDataValue::Object(vec![0xab, 0xcd])).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "object", "value": "abcd" })
+        );
+        let json = serde_json::to_value(FormDataValue::Json(r#"{"a":1}"#.into())).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "json", "value": { "a": 1 } })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn reject_invalid_values() {
+        assert!(serde_json::to_string(&FormDataValue::Json("{".into())).is_err());
+        assert!(serde_json::from_str::<FormDataValue>(r#"{"type":"u64","value":"x"}"#).is_err());
+        assert!(
+            serde_json::from_str::<FormDataValue>(r#"{"type":"object","value":"zz"}"#).is_err()
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn form_data_round_trip() {
+        let mut data = FormData::new(Some("id".into()));
+        data.add_string("name", "value".into());
+        data.add_u64("amount", u64::MAX);
+        data.add_object("nested", FormData::new(None)).unwrap();
+        assert_eq!(FormData::from_json(&data.to_json().unwrap()).unwrap(), data);
+    }
+
+    #[wasm_bindgen_test]
+    fn nested_json_round_trip() {
+        let value = FormDataValue::Json(r#"{"a":{"b":[1,{"c":null}],"d":"e"},"f":[]}"#.into());
+        assert_eq!(round_trip(value.clone()), value);
+        let value = FormDataValue::Json(r#"[[1,2],[],{"a":[true,false]}]"#.into());
+        assert_eq!(round_trip(value.clone()), value);
+    }
+
+    #[wasm_bindgen_test]
+    fn repeater_entries_round_trip() {
+        let mut first = FormData::new(None);
+        first.add_string("name", "a".into());
+        first.add_u64("amount", u64::MAX);
+        let mut second = FormData::new(None);
+        second.add_list("tags", vec!["x".into(), "y".into()]);
+        let entries = vec![first.values, second.values];
+
+        let mut data = FormData::new(None);
+        data.add_object_json("entries", &entries).unwrap();
+        let data = FormData::from_json(&data.to_json().unwrap()).unwrap();
+        let loaded = data
+            .get_object_json::<Vec<BTreeMap<String, FormDataValue>>>("entries")
+            .unwrap();
+        assert_eq!(loaded, Some(entries));
+    }
+
+    #[wasm_bindgen_test]
+    fn legacy_object_round_trip() {
+        let mut entry = FormData::new(None);
+        entry.add_string("name", "a".into());
+        let entries = vec![entry.clone(), FormData::new(None)];
+
+        let mut data = FormData::new(None);
+        data.add_object("entries", entries.clone()).unwrap();
+        let data = FormData::from_json(&data.to_json().unwrap()).unwrap();
+        assert_eq!(
+            data.get_object::<Vec<FormData>>("entries").unwrap(),
+            Some(entries)
+        );
+    }
+}
@@ -4,10 +4,12 @@ use crate::prelude::*;
 //use crate::controls::element_wrapper::ElementWrapper;
 //use crate::attributes::Attributes;
 //use crate::docs::Docs;
+use crate::dirty::DirtyTracker;
 use crate::view::Layout;
 use workflow_i18n::i18n;
-use workflow_ux::layout::Elemental;
+use workflow_ux::layout::{DefaultFunctions, Elemental};
 use workflow_ux::result::Result;
+use workflow_ux::validation::validate_layout;
 //use workflow_html::{html, Render};
 //use workflow_ux::form::FormHandlers;
 
@@ -72,6 +74,24 @@ impl FormFooter {
         Ok(())
     }
 
+    pub fn set_submit_enabled(&self, enabled: bool) -> Result<()> {
+        if enabled {
+            self.submit_btn.element.remove_attribute("disabled")?;
+        } else {
+            self.submit_btn.element.set_attribute("disabled", "true")?;
+        }
+        Ok(())
+    }
+
+    /// Enables the submit button only while the form has unsaved changes
+    pub fn bind_dirty_tracker(&self, tracker: &DirtyTracker) -> Result<()> {
+        self.set_submit_enabled(tracker.is_dirty()?)?;
+        let this = self.clone();
+        tracker.on_change(Box::new(move |dirty| -> Result<()> {
+            this.set_submit_enabled(dirty)
+        }))
+    }
+
     pub fn init(&mut self) -> Result<()> {
         let cb_opt = self.on_submit_click_cb.clone();
         self.submit_btn.on_click(move |_e| -> Result<()> {
@@ -95,37 +115,55 @@ impl FormFooter {
 
     pub fn on_submit<F>(&self, layout: Arc<Mutex<F>>, struct_name: String)
     where
-        F: FormHandler + Elemental + Clone + 'static,
+        F: FormHandler + DefaultFunctions + Elemental + Clone + 'static,
     {
-        let form_handler = {
-            layout
-                .lock()
-                .unwrap_or_else(|_| {
-                    panic!(
-                        "Unable to lock form {} for footer submit action.",
-                        &struct_name
-                    )
-                })
-                .clone()
-        };
+        let form_handler = form_handler(&layout, &struct_name);
 
         workflow_core::task::wasm::dispatch(async move {
-            let action = form_handler.submit();
-            action.await
+            if !validate_layout(&form_handler).await? {
+                return Ok(());
+            }
+            form_handler.submit().await?;
+            // a submitted form no longer needs its draft
+            if let Some(draft) = form_handler.draft() {
+                draft.finish()?;
+            }
+            if let Some(tracker) = form_handler.dirty_tracker() {
+                tracker.snapshot(&form_handler)?;
+            }
+            Ok(())
         })
     }
 
     pub fn bind_layout<F, D>(&mut self, struct_name: String, view: Arc<Layout<F, D>>) -> Result<()>
     where
-        F: FormHandler + Elemental + Send + Clone + 'static,
+        F: FormHandler + DefaultFunctions + Elemental + Send + Clone + 'static,
         D: Send + 'static,
     {
-        let layout_clone = view.layout();
-        let this = self.clone();
+        // the layout owns this footer
+        let view = Arc::downgrade(&view);
         self.submit_btn.on_click(move |_| -> Result<()> {
-            let struct_name = struct_name.clone();
-            let layout = layout_clone.clone();
-            this.on_submit(layout, struct_name);
+            let view = match view.upgrade() {
+                Some(view) => view,
+                None => return Ok(()),
+            };
+            let form_handler = form_handler(&view.layout(), &struct_name);
+            workflow_core::task::wasm::dispatch(async move {
+                // waits for pending async validators before submitting
+                if !validate_layout(&form_handler).await? {
+                    return Ok(());
+                }
+                form_handler.submit().await?;
+                if let Some(draft) = form_handler.draft() {
+                    draft.finish()?;
+                }
+                // submitted values are the new clean state
+                if let Some(tracker) = form_handler.dirty_tracker() {
+                    tracker.snapshot(&form_handler)?;
+                }
+                // submitted changes no longer veto the view eviction
+                view.set_dirty(false)
+            });
             Ok(())
         })?;
 
@@ -142,3 +180,15 @@ impl FormFooter {
     }
     */
 }
+
+fn form_handler<F: Clone>(layout: &Arc<Mutex<F>>, struct_name: &str) -> F {
+    layout
+        .lock()
+        .unwrap_or_else(|_| {
+            panic!(
+                "Unable to lock form {} for footer submit action.",
+                struct_name
+            )
+        })
+        .clone()
+}
@@ -1,3 +1,4 @@
+use std::any::Any;
 use std::fmt;
 use std::sync::Mutex;
 use wasm_bindgen::JsCast;
@@ -7,10 +8,18 @@ use workflow_ux::result::Result;
 
 use crate::attributes::Attributes;
 use crate::controls::{form::FormControl, stage_footer};
+use crate::dirty::DirtyTracker;
 use crate::docs::Docs;
+use crate::draft::Draft;
+use crate::error::error;
+use crate::form::FormData;
+use crate::icon::Icon;
 use crate::markdown::markdown_to_html;
+use crate::validation::AsyncValidators;
 
-use web_sys::Element;
+use web_sys::{Element, HtmlElement};
+
+pub static CSS: &str = include_str!("layout.css");
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ElementLayoutStyle {
@@ -45,13 +54,145 @@ impl fmt::Display for ElementLayoutStyle {
     }
 }
 
+/// Responsive grid of a layout, declared with the layout attributes
+/// `columns`, `columns_sm`, `columns_md` and `columns_lg` (column count
+/// from the given breakpoint up) and `labels = "inline"` or `"stacked"`.
+/// Fields span columns with `span = n` and share a row with `row = "name"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutGrid {
+    pub columns: Option<u32>,
+    pub columns_sm: Option<u32>,
+    pub columns_md: Option<u32>,
+    pub columns_lg: Option<u32>,
+    pub inline_labels: bool,
+}
+
+impl LayoutGrid {
+    /// Returns `None` if `attributes` do not declare a grid
+    pub fn from_attributes(attributes: &Attributes) -> Result<Option<Self>> {
+        let grid = Self {
+            columns: grid_count(attributes, "columns")?,
+            columns_sm: grid_count(attributes, "columns_sm")?,
+            columns_md: grid_count(attributes, "columns_md")?,
+            columns_lg: grid_count(attributes, "columns_lg")?,
+            inline_labels: match attributes.get("labels").map(|v| v.as_str()) {
+                Some("inline") => true,
+                Some("stacked") | None => false,
+                Some(labels) => {
+                    return Err(error!(
+                        "ElementLayout: `labels` expects `inline` or `stacked`, got `{}`",
+                        labels
+                    ))
+                }
+            },
+        };
+        Ok((grid != Self::default()).then_some(grid))
+    }
+
+    pub fn apply(&self, element: &Element) -> Result<()> {
+        element.class_list().add_1("layout-grid")?;
+        element
+            .class_list()
+            .toggle_with_force("inline-labels", self.inline_labels)?;
+        let style = element.unchecked_ref::<HtmlElement>().style();
+        for (property, columns) in [
+            ("--layout-columns", self.columns),
+            ("--layout-columns-sm", self.columns_sm),
+            ("--layout-columns-md", self.columns_md),
+            ("--layout-columns-lg", self.columns_lg),
+        ] {
+            if let Some(columns) = columns {
+                style.set_property(property, &columns.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn grid_count(attributes: &Attributes, name: &str) -> Result<Option<u32>> {
+    match attributes.get(name) {
+        Some(value) => match value.parse::<u32>() {
+            Ok(count) if count > 0 => Ok(Some(count)),
+            _ => Err(error!(
+                "ElementLayout: `{}` expects a positive number, got `{}`",
+                name, value
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Grid placement attributes of a child, not forwarded to its `<flow-form-control>`
+const GRID_CHILD_ATTRIBUTES: [&str; 2] = ["span", "row"];
+
 pub trait DefaultFunctions {
     fn init(&self) -> Result<()> {
         Ok(())
     }
-    fn validate_stage(&self) -> Result<bool> {
+    fn validate(&self) -> Result<bool> {
         Ok(true)
     }
+    fn validate_stage(&self) -> Result<bool> {
+        self.validate()
+    }
+    fn async_validators(&self) -> Option<AsyncValidators> {
+        None
+    }
+    /// Sets control values from the matching `data` keys
+    fn load_form_data(&self, _data: &FormData) -> Result<()> {
+        Ok(())
+    }
+    /// Adds control values to `data`, skipping sensitive controls unless `sensitive` is set
+    fn collect_form_data(&self, _data: &mut FormData, _sensitive: bool) -> Result<()> {
+        Ok(())
+    }
+    /// Current control values
+    fn form_data(&self) -> Result<FormData> {
+        let mut data = FormData::new(None);
+        self.collect_form_data(&mut data, true)?;
+        Ok(data)
+    }
+    fn dirty_tracker(&self) -> Option<DirtyTracker> {
+        None
+    }
+    /// Whether any control value differs from the initial (or last saved) one
+    fn is_dirty(&self) -> Result<bool> {
+        match self.dirty_tracker() {
+            Some(tracker) => Ok(!tracker.dirty_fields(self)?.is_empty()),
+            None => Ok(false),
+        }
+    }
+    fn dirty_fields(&self) -> Result<Vec<String>> {
+        match self.dirty_tracker() {
+            Some(tracker) => tracker.dirty_fields(self),
+            None => Ok(Vec::new()),
+        }
+    }
+    /// Restores the initial (or last saved) control values
+    fn reset(&self) -> Result<()> {
+        match self.dirty_tracker() {
+            Some(tracker) => tracker.reset(self),
+            None => Ok(()),
+        }
+    }
+    fn on_dirty_change(&self, callback: CallbackFn<bool>) -> Result<()> {
+        match self.dirty_tracker() {
+            Some(tracker) => tracker.on_change(callback),
+            None => Ok(()),
+        }
+    }
+    /// Draft storage declared with `#[form(draft = "key")]`
+    fn draft(&self) -> Option<Draft> {
+        None
+    }
+    /// Title of a layout used as a form stage, i.e. `#[section(title = "...")]`
+    fn stage_title(&self) -> Option<String> {
+        None
+    }
+    /// Icon of a layout used as a form stage, i.e. `#[section(icon = "...")]`
+    fn stage_icon(&self) -> Option<Icon> {
+        None
+    }
     /*
     fn submit(&self) -> Result<bool>{
         log_trace!("DefaultFunctions::submit()");
@@ -60,10 +201,34 @@ pub trait DefaultFunctions {
     */
 }
 
+/// Layouts created at runtime, i.e. the entries of a
+/// [`Repeater`](crate::controls::repeater::Repeater); implemented by the layout macros.
+pub trait DynamicLayout: Sized {
+    fn create(parent_layout: &ElementLayout, attributes: &Attributes, docs: &Docs) -> Result<Self>;
+}
+
 pub trait Elemental {
     fn element(&self) -> Element;
 }
 
+/// Entry of the keyed child registry of an [`ElementLayout`]
+#[derive(Clone)]
+pub struct LayoutChild {
+    pub key: String,
+    /// Node inserted into the layout, i.e. the `<flow-form-control>` wrapping a section field
+    pub node: Element,
+    control: Option<Arc<dyn Any>>,
+}
+
+impl fmt::Debug for LayoutChild {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LayoutChild")
+            .field("key", &self.key)
+            .field("node", &self.node)
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct ElementLayoutInner {
     pub id: Id,
@@ -71,6 +236,8 @@ pub struct ElementLayoutInner {
     pub element: Element,
     pub attributes: Attributes,
     pub layout_style: ElementLayoutStyle,
+    /// Keyed children in document order
+    pub children: Vec<LayoutChild>,
 }
 
 // pub struct ElementLayout {
@@ -125,6 +292,7 @@ impl ElementLayout {
             layout_style: ElementLayoutStyle::Html,
             attributes: Attributes::new(), //attributes.clone(),
             element,
+            children: Vec::new(),
         })));
         Ok(layout)
     }
@@ -146,6 +314,7 @@ impl ElementLayout {
             layout_style,
             attributes, //: attributes.clone(),
             element,
+            children: Vec::new(),
         })));
         Ok(layout)
     }
@@ -169,6 +338,9 @@ impl ElementLayout {
         let id = Id::new();
         element.set_id(&id.to_string());
         element.set_class_name(&format!("{}-container", layout_style.get_type()));
+        if let Some(grid) = LayoutGrid::from_attributes(attributes)? {
+            grid.apply(&element)?;
+        }
         parent.element.append_child(&element)?;
         let layout = ElementLayout(Arc::new(Mutex::new(ElementLayoutInner {
             id, //: id.into(),
@@ -176,6 +348,7 @@ impl ElementLayout {
             layout_style,
             attributes: attributes.clone(),
             element,
+            children: Vec::new(),
         })));
         Ok(layout)
     }
@@ -189,6 +362,53 @@ impl ElementLayout {
         let layout = self
             .inner()
             .ok_or("ElementLayout::append_child() - faulure to lock parent layout inner")?;
+        let node = Self::child_node(&layout, child, attributes, docs)?;
+        match Self::row_container(&layout, attributes)? {
+            Some((row, created)) => {
+                if created {
+                    layout.element.append_child(&row)?;
+                }
+                row.append_child(&node)?;
+            }
+            None => {
+                layout.element.append_child(&node)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Row grouping the children declared with the same `row` attribute;
+    /// the flag is set if the row has been created and still needs to be placed
+    fn row_container(
+        layout: &ElementLayoutInner,
+        attributes: &Attributes,
+    ) -> Result<Option<(Element, bool)>> {
+        let name = match attributes.get("row") {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        let rows = layout.element.children();
+        for index in 0..rows.length() {
+            if let Some(row) = rows.item(index) {
+                if row.class_list().contains("layout-row")
+                    && row.get_attribute("data-row").as_ref() == Some(name)
+                {
+                    return Ok(Some((row, false)));
+                }
+            }
+        }
+        let row = create_el("div.layout-row", vec![("data-row", name.as_str())], None)?;
+        Ok(Some((row, true)))
+    }
+
+    /// Node holding `child` in this layout, i.e. the child itself
+    /// or the `<flow-form-control>` wrapping a section field
+    fn child_node(
+        layout: &ElementLayoutInner,
+        child: &Element,
+        attributes: &Attributes,
+        docs: &Docs,
+    ) -> Result<Element> {
         let container = match &layout.layout_style {
             ElementLayoutStyle::Form => None,
             ElementLayoutStyle::Stage => None,
@@ -205,7 +425,7 @@ impl ElementLayout {
                 }
 
                 for (k, v) in attributes.iter() {
-                    if !k.eq("title") {
+                    if !k.eq("title") && !GRID_CHILD_ATTRIBUTES.contains(&k.as_str()) {
                         if k.eq("no_info") {
                             form_control.set_attribute("no-info", v)?;
                         } else if k.eq("no_icon") {
@@ -235,19 +455,193 @@ impl ElementLayout {
             ElementLayoutStyle::Html => None,
         };
 
-        match container {
+        let node = match container {
             Some(container) => {
                 container.append_child(child)?;
-                layout.element.append_child(&container)?;
+                container
             }
-            None => {
-                layout.element.append_child(child)?;
+            None => child.clone(),
+        };
+        if let Some(span) = grid_count(attributes, "span")? {
+            node.unchecked_ref::<HtmlElement>()
+                .style()
+                .set_property("--layout-span", &span.to_string())?;
+        }
+        Ok(node)
+    }
+
+    /// Appends `child` and registers it under `key` without its control;
+    /// such children can be moved and removed but not retrieved with
+    /// [`get`](Self::get). The layout macros register their fields with
+    /// [`insert_at`](Self::insert_at) (this is used for generic layouts).
+    pub fn append_keyed_child(
+        &self,
+        key: &str,
+        child: &Element,
+        attributes: &Attributes,
+        docs: &Docs,
+    ) -> Result<()> {
+        self.insert_node(usize::MAX, key, None, child, attributes, docs)
+    }
+
+    /// Inserts `child` at `index` of the keyed children (appends if `index`
+    /// is out of range) and registers `control` under `key`
+    pub fn insert_at<T: Clone + 'static>(
+        &self,
+        index: usize,
+        key: &str,
+        control: &T,
+        child: &Element,
+        attributes: &Attributes,
+        docs: &Docs,
+    ) -> Result<()> {
+        let control: Arc<dyn Any> = Arc::new(control.clone());
+        self.insert_node(index, key, Some(control), child, attributes, docs)
+    }
+
+    fn insert_node(
+        &self,
+        index: usize,
+        key: &str,
+        control: Option<Arc<dyn Any>>,
+        child: &Element,
+        attributes: &Attributes,
+        docs: &Docs,
+    ) -> Result<()> {
+        let mut layout = self
+            .inner()
+            .ok_or("ElementLayout::insert_at() - failure to lock layout inner")?;
+        if layout.children.iter().any(|c| c.key == key) {
+            return Err(error!("ElementLayout: child `{}` already exists", key));
+        }
+        let node = Self::child_node(&layout, child, attributes, docs)?;
+        let index = index.min(layout.children.len());
+        match Self::row_container(&layout, attributes)? {
+            Some((row, created)) => {
+                if created {
+                    Self::place_node(&layout, index, &row)?;
+                }
+                row.append_child(&node)?;
+            }
+            None => Self::place_node(&layout, index, &node)?,
+        }
+        layout.children.insert(
+            index,
+            LayoutChild {
+                key: key.to_string(),
+                node,
+                control,
+            },
+        );
+        Ok(())
+    }
+
+    /// Places `node` in front of the keyed child at `index`, or after the last one;
+    /// children sharing a row are placed around as a whole
+    fn place_node(layout: &ElementLayoutInner, index: usize, node: &Element) -> Result<()> {
+        let anchor =
+            |child: &LayoutChild| Self::row_of(&child.node).unwrap_or_else(|| child.node.clone());
+        match (layout.children.get(index), layout.children.last()) {
+            (Some(next), _) => anchor(next).before_with_node_1(node)?,
+            (None, Some(last)) => anchor(last).after_with_node_1(node)?,
+            (None, None) => {
+                layout.element.append_child(node)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `.layout-row` holding `node`, if any
+    fn row_of(node: &Element) -> Option<Element> {
+        node.parent_element()
+            .filter(|parent| parent.class_list().contains("layout-row"))
+    }
+
+    /// Removes `row` once its last child has been moved or removed
+    fn remove_empty_row(row: Option<Element>) {
+        if let Some(row) = row {
+            if row.child_element_count() == 0 {
+                row.remove();
             }
         }
+    }
 
+    /// Replaces the child registered under `key`, keeping its position.
+    /// The functions generated by the layout macros (validation, form data)
+    /// use the new control if it is of the type of the field, otherwise
+    /// they skip the field.
+    pub fn replace<T: Clone + 'static>(
+        &self,
+        key: &str,
+        control: &T,
+        child: &Element,
+        attributes: &Attributes,
+        docs: &Docs,
+    ) -> Result<()> {
+        let index = self
+            .index_of(key)
+            .ok_or_else(|| error!("ElementLayout: child `{}` not found", key))?;
+        self.remove(key)?;
+        self.insert_at(index, key, control, child, attributes, docs)
+    }
+
+    /// Removes the child registered under `key` from the layout;
+    /// returns `false` if there is no such child. A removed field of a
+    /// layout macro is no longer validated, loaded or collected.
+    pub fn remove(&self, key: &str) -> Result<bool> {
+        let mut layout = self
+            .inner()
+            .ok_or("ElementLayout::remove() - failure to lock layout inner")?;
+        match layout.children.iter().position(|c| c.key == key) {
+            Some(index) => {
+                let node = layout.children.remove(index).node;
+                let row = Self::row_of(&node);
+                node.remove();
+                Self::remove_empty_row(row);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Moves the child registered under `key` to `index` of the keyed children
+    pub fn move_to(&self, key: &str, index: usize) -> Result<()> {
+        let mut layout = self
+            .inner()
+            .ok_or("ElementLayout::move_to() - failure to lock layout inner")?;
+        let from = layout
+            .children
+            .iter()
+            .position(|c| c.key == key)
+            .ok_or_else(|| error!("ElementLayout: child `{}` not found", key))?;
+        let child = layout.children.remove(from);
+        let index = index.min(layout.children.len());
+        // a moved child leaves its row
+        let row = Self::row_of(&child.node);
+        Self::place_node(&layout, index, &child.node)?;
+        Self::remove_empty_row(row);
+        layout.children.insert(index, child);
         Ok(())
     }
 
+    /// Control registered under `key`, if it is of type `T`
+    pub fn get<T: Clone + 'static>(&self, key: &str) -> Option<T> {
+        let layout = self.inner()?;
+        let child = layout.children.iter().find(|c| c.key == key)?;
+        child.control.as_ref()?.downcast_ref::<T>().cloned()
+    }
+
+    pub fn index_of(&self, key: &str) -> Option<usize> {
+        self.inner()?.children.iter().position(|c| c.key == key)
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        match self.inner() {
+            Some(layout) => layout.children.iter().map(|c| c.key.clone()).collect(),
+            None => Vec::new(),
+        }
+    }
+
     pub fn init_footer(&self) -> Result<()> {
         let layout = self
             .inner()
@@ -298,3 +692,50 @@ impl ElementLayout {
         Ok(footer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn attrs(list: &[(&str, &str)]) -> Attributes {
+        list.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[wasm_bindgen_test]
+    fn no_grid() {
+        let attributes = attrs(&[("title", "Account")]);
+        assert_eq!(LayoutGrid::from_attributes(&attributes).unwrap(), None);
+        let attributes = attrs(&[("labels", "stacked")]);
+        assert_eq!(LayoutGrid::from_attributes(&attributes).unwrap(), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn grid() {
+        let attributes = attrs(&[("columns", "1"), ("columns_md", "2"), ("labels", "inline")]);
+        assert_eq!(
+            LayoutGrid::from_attributes(&attributes).unwrap(),
+            Some(LayoutGrid {
+                columns: Some(1),
+                columns_md: Some(2),
+                inline_labels: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_grid() {
+        for (name, value) in [
+            ("columns", "0"),
+            ("columns_sm", "-1"),
+            ("columns_lg", "many"),
+            ("labels", "left"),
+        ] {
+            let attributes = attrs(&[(name, value)]);
+            assert!(LayoutGrid::from_attributes(&attributes).is_err());
+        }
+    }
+}
@@ -4,14 +4,21 @@ extern crate self as workflow_ux;
 pub mod prelude;
 
 pub mod attributes;
+pub mod condition;
 pub mod control;
 pub mod controls;
+pub mod descriptor;
 pub mod docs;
+pub mod dirty;
 pub mod dom;
+pub mod draft;
 pub mod error;
 pub mod icon;
 pub mod menu;
 pub mod result;
+pub mod router;
+pub mod schema;
+pub mod state;
 pub mod theme;
 pub mod utils;
 pub use menu::app_menu;
@@ -25,6 +32,8 @@ pub mod image;
 pub mod layout;
 pub mod link;
 pub mod module;
+pub mod observer;
+pub mod overlay;
 pub mod panel;
 pub mod view;
 pub mod wasm;
@@ -37,9 +46,12 @@ pub mod markdown;
 pub mod pagination;
 pub mod progress;
 pub mod qrcode;
+pub mod stepper;
 pub mod style;
 pub mod task;
+pub mod transition;
 pub mod user_agent;
+pub mod validation;
 pub use workflow_async_trait::{async_trait, async_trait_with_send, async_trait_without_send};
 pub mod data_field;
 pub use data_field::DataField;
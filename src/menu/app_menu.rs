@@ -49,6 +49,25 @@ impl AppMenu {
     }
 
     pub fn update_bottom_menus(&self, menus: Option<Vec<BottomMenuItem>>) -> Result<()> {
+        self.update_bottom_menus_with_back(menus, None)
+    }
+
+    /// Updates the bottom menu items, placing the supplied
+    /// back navigation item in front of the view menus.
+    pub fn update_bottom_menus_with_back(
+        &self,
+        menus: Option<Vec<BottomMenuItem>>,
+        back: Option<BottomMenuItem>,
+    ) -> Result<()> {
+        let menus = match back {
+            Some(back) => {
+                let mut list = vec![back];
+                list.extend(menus.unwrap_or_default());
+                Some(list)
+            }
+            None => menus,
+        };
+
         if let Some(bottom) = self.bottom.as_ref() {
             let m = bottom.clone();
             let mut menu = m.lock().expect("Unable to lock BottomMenu");
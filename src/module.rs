@@ -8,6 +8,9 @@ use workflow_async_trait::async_trait_without_send;
 use workflow_ux::error::Error;
 use workflow_ux::prelude::*;
 use workflow_ux::result::Result;
+use workflow_ux::overlay;
+use workflow_ux::router;
+use workflow_ux::state::ViewState;
 
 use downcast::{downcast_sync, AnySync};
 
@@ -29,6 +32,42 @@ pub trait ModuleInterface: AnySync {
         Ok(())
     }
 
+    /// Counterpart of [`ModuleInterface::evict`] for views removed
+    /// from a [`view::ContainerStack`].
+    async fn evict_stacked(
+        self: Arc<Self>,
+        _stack: &Arc<view::ContainerStack>,
+        _view: Arc<dyn view::View>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Counterpart of [`ModuleInterface::evict`] for views removed
+    /// from the [`overlay::OverlayStack`].
+    async fn evict_overlay(
+        self: Arc<Self>,
+        _overlays: &Arc<overlay::OverlayStack>,
+        _view: Arc<dyn view::View>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Produces the view for a route registered with [`router::Router::register`].
+    /// `route` is the pattern the route was registered with.
+    async fn route(
+        self: Arc<Self>,
+        _route: &str,
+        _params: &router::Params,
+    ) -> Result<Option<Arc<dyn view::View>>> {
+        Ok(None)
+    }
+
+    /// Rebuilds the view from the state persisted by a [`view::Container`]
+    /// before the page was reloaded (see [`view::View::snapshot`]).
+    async fn restore(self: Arc<Self>, _state: &ViewState) -> Result<Option<Arc<dyn view::View>>> {
+        Ok(None)
+    }
+
     // TODO - generate and inject HTML into the render view
     // async fn render(self : Arc<Self>, _account : &AccountDataReference) -> Result<()> { Ok(()) }
 
@@ -151,6 +190,18 @@ pub fn get_module(name: &str) -> Option<Arc<Module>> {
         .cloned()
 }
 
+/// Name the module with the supplied interface was registered with.
+pub fn get_module_name(iface: &Arc<dyn ModuleInterface>) -> Option<String> {
+    registry()
+        .read()
+        .ok()?
+        .iter()
+        .find(|(_, module)| {
+            Arc::as_ptr(&module.iface) as *const () == Arc::as_ptr(iface) as *const ()
+        })
+        .map(|(name, _)| name.clone())
+}
+
 pub fn get_interface<T>(name: &str) -> Option<Arc<T>>
 // pub fn get<T>() -> Result<Option<Arc<T>>>
 where
@@ -0,0 +1,169 @@
+use crate::prelude::*;
+use crate::result::Result;
+use crate::view::View;
+use std::sync::Weak;
+use workflow_wasm::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = ResizeObserver)]
+    #[derive(Debug, Clone)]
+    pub type ResizeObserver;
+
+    #[wasm_bindgen(catch, constructor, js_class = "ResizeObserver")]
+    pub fn new(callback: &js_sys::Function) -> std::result::Result<ResizeObserver, JsValue>;
+
+    #[wasm_bindgen(method, js_class = "ResizeObserver", js_name = observe)]
+    pub fn observe(this: &ResizeObserver, target: &Element);
+
+    #[wasm_bindgen(method, js_class = "ResizeObserver", js_name = disconnect)]
+    pub fn disconnect(this: &ResizeObserver);
+
+    #[wasm_bindgen(js_name = ResizeObserverEntry)]
+    #[derive(Debug, Clone)]
+    pub type ResizeObserverEntry;
+
+    #[wasm_bindgen(method, getter, js_class = "ResizeObserverEntry", js_name = contentRect)]
+    pub fn content_rect(this: &ResizeObserverEntry) -> web_sys::DomRect;
+
+    #[wasm_bindgen(js_name = IntersectionObserver)]
+    #[derive(Debug, Clone)]
+    pub type IntersectionObserver;
+
+    #[wasm_bindgen(catch, constructor, js_class = "IntersectionObserver")]
+    pub fn new(callback: &js_sys::Function) -> std::result::Result<IntersectionObserver, JsValue>;
+
+    #[wasm_bindgen(method, js_class = "IntersectionObserver", js_name = observe)]
+    pub fn observe(this: &IntersectionObserver, target: &Element);
+
+    #[wasm_bindgen(method, js_class = "IntersectionObserver", js_name = disconnect)]
+    pub fn disconnect(this: &IntersectionObserver);
+
+    #[wasm_bindgen(js_name = IntersectionObserverEntry)]
+    #[derive(Debug, Clone)]
+    pub type IntersectionObserverEntry;
+
+    #[wasm_bindgen(method, getter, js_class = "IntersectionObserverEntry", js_name = isIntersecting)]
+    pub fn is_intersecting(this: &IntersectionObserverEntry) -> bool;
+}
+
+#[derive(Default)]
+struct Visibility {
+    intersecting: bool,
+    visible: Option<bool>,
+}
+
+impl Visibility {
+    /// Returns the new visibility if it has changed.
+    fn update(&mut self) -> Option<bool> {
+        let visible = self.intersecting && !document().hidden();
+        if self.visible == Some(visible) {
+            None
+        } else {
+            self.visible = Some(visible);
+            Some(visible)
+        }
+    }
+}
+
+/// Drives [`View::on_resize`] and [`View::on_visibility`] hooks of a
+/// displayed view using `ResizeObserver`, `IntersectionObserver` and
+/// the document `visibilitychange` event.  Observation stops when
+/// the observer is dropped.
+pub struct ViewObserver {
+    resize: ResizeObserver,
+    intersection: IntersectionObserver,
+    visibility_listener: Callback<dyn FnMut(web_sys::Event) -> Result<()>>,
+    _callbacks: CallbackMap,
+}
+
+impl ViewObserver {
+    pub fn try_new(view: &Arc<dyn View>) -> Result<ViewObserver> {
+        let element = view.element();
+        let visibility = Arc::new(Mutex::new(Visibility::default()));
+        let callbacks = CallbackMap::new();
+
+        let resize = {
+            let view: Weak<dyn View> = Arc::downgrade(view);
+            let callback = callback!(move |entries: js_sys::Array| -> Result<()> {
+                if let Some(view) = view.upgrade() {
+                    for entry in entries.iter() {
+                        let rect = entry.unchecked_into::<ResizeObserverEntry>().content_rect();
+                        view.on_resize(rect.width(), rect.height())?;
+                    }
+                }
+                Ok(())
+            });
+            let observer = ResizeObserver::new(callback.as_ref())?;
+            callbacks.retain(callback)?;
+            observer.observe(&element);
+            observer
+        };
+
+        let intersection = {
+            let view: Weak<dyn View> = Arc::downgrade(view);
+            let visibility = visibility.clone();
+            let callback = callback!(move |entries: js_sys::Array| -> Result<()> {
+                if let Some(view) = view.upgrade() {
+                    let changed = {
+                        let mut visibility = visibility.lock()?;
+                        for entry in entries.iter() {
+                            visibility.intersecting = entry
+                                .unchecked_into::<IntersectionObserverEntry>()
+                                .is_intersecting();
+                        }
+                        visibility.update()
+                    };
+                    if let Some(visible) = changed {
+                        view.on_visibility(visible)?;
+                    }
+                }
+                Ok(())
+            });
+            let observer = IntersectionObserver::new(callback.as_ref())?;
+            callbacks.retain(callback)?;
+            observer.observe(&element);
+            observer
+        };
+
+        let visibility_listener = {
+            let view: Weak<dyn View> = Arc::downgrade(view);
+            callback!(move |_event: web_sys::Event| -> Result<()> {
+                if let Some(view) = view.upgrade() {
+                    let changed = visibility.lock()?.update();
+                    if let Some(visible) = changed {
+                        view.on_visibility(visible)?;
+                    }
+                }
+                Ok(())
+            })
+        };
+        document()
+            .add_event_listener_with_callback("visibilitychange", visibility_listener.as_ref())?;
+
+        Ok(ViewObserver {
+            resize,
+            intersection,
+            visibility_listener,
+            _callbacks: callbacks,
+        })
+    }
+
+    pub fn disconnect(&self) -> Result<()> {
+        self.resize.disconnect();
+        self.intersection.disconnect();
+        document().remove_event_listener_with_callback(
+            "visibilitychange",
+            self.visibility_listener.as_ref(),
+        )?;
+        Ok(())
+    }
+}
+
+impl Drop for ViewObserver {
+    fn drop(&mut self) {
+        if let Err(err) = self.disconnect() {
+            log_error!("ViewObserver: unable to disconnect: {}", err);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use crate::error::{error, Error};
+use crate::icon::Icon;
+use crate::observer::ViewObserver;
+use crate::prelude::*;
+use crate::result::Result;
+use crate::view::{query_eviction, same_view, View};
+use std::any::Any;
+use workflow_core::channel::{oneshot, Sender};
+use workflow_wasm::callback::CallbackMap;
+use workflow_wasm::prelude::callback;
+
+pub static CSS: &str = include_str!("overlay.css");
+
+static mut OVERLAYS: Option<Arc<OverlayStack>> = None;
+
+/// Value supplied to [`OverlayStack::dismiss`], `None` if the overlay was cancelled.
+type OverlayResult = Option<Box<dyn Any>>;
+
+struct Overlay {
+    id: Id,
+    element: Element,
+    view: Arc<dyn View>,
+    sender: Sender<OverlayResult>,
+    _observer: ViewObserver,
+}
+
+/// Stack of views presented as modal overlays on top of the workspace
+/// (a centered sheet on desktop, full-screen on mobile).
+pub struct OverlayStack {
+    element: Element,
+    overlays: Arc<Mutex<Vec<Overlay>>>,
+    callbacks: CallbackMap,
+}
+
+unsafe impl Send for OverlayStack {}
+unsafe impl Sync for OverlayStack {}
+
+impl OverlayStack {
+    fn try_new() -> Result<Arc<OverlayStack>> {
+        let element = create_el("div.workspace-overlays", vec![], None)?;
+        document()
+            .body()
+            .ok_or(Error::UnableToGetBody)?
+            .append_child(&element)?;
+
+        let stack = Arc::new(OverlayStack {
+            element,
+            overlays: Arc::new(Mutex::new(Vec::new())),
+            callbacks: CallbackMap::new(),
+        });
+        stack.init()?;
+
+        Ok(stack)
+    }
+
+    fn init(self: &Arc<Self>) -> Result<()> {
+        let this = Arc::downgrade(self);
+        let callback = callback!(move |event: web_sys::MouseEvent| -> Result<()> {
+            let target = match event.target().and_then(|t| t.dyn_into::<Element>().ok()) {
+                Some(target) => target,
+                None => return Ok(()),
+            };
+            let cancel = target.class_list().contains("workspace-overlay-mask")
+                || target.closest(".workspace-overlay-close")?.is_some();
+            let id = match target.closest("[data-overlay]")? {
+                Some(overlay) if cancel => {
+                    overlay.get_attribute("data-overlay").unwrap_or_default()
+                }
+                _ => return Ok(()),
+            };
+            let id = id
+                .parse::<Id>()
+                .map_err(|err| error!("Overlay: invalid overlay id `{}`: {}", id, err))?;
+            if let Some(this) = this.upgrade() {
+                workflow_core::task::wasm::dispatch(async move {
+                    if let Err(err) = this.remove(id, None, true).await {
+                        log_error!("Overlay: unable to cancel overlay: {}", err);
+                    }
+                });
+            }
+            Ok(())
+        });
+        self.element
+            .add_event_listener_with_callback("click", callback.as_ref())?;
+        self.callbacks.retain(callback)?;
+        Ok(())
+    }
+
+    /// Presents the view on top of the stack.  Resolves with the value
+    /// supplied to [`OverlayStack::dismiss`] or with
+    /// [`Error::OverlayCancelled`] if the overlay is closed without a value.
+    pub async fn present<T: 'static>(&self, view: Arc<dyn View>) -> Result<T> {
+        let (sender, receiver) = oneshot();
+        let id = Id::new();
+        let element = create_el(
+            "div.workspace-overlay",
+            vec![("data-overlay", &id.to_string())],
+            None,
+        )?;
+        let mask = create_el("div.workspace-overlay-mask", vec![], None)?;
+        let sheet = create_el("div.workspace-overlay-sheet", vec![], None)?;
+        let close = Icon::css("close").element()?;
+        close.class_list().add_1("workspace-overlay-close")?;
+        sheet.append_child(&close)?;
+        sheet.append_child(&view.element())?;
+        element.append_child(&mask)?;
+        element.append_child(&sheet)?;
+        self.element.append_child(&element)?;
+
+        view.subscribe()?;
+        let observer = ViewObserver::try_new(&view)?;
+        self.overlays.lock()?.push(Overlay {
+            id,
+            element,
+            view: view.clone(),
+            sender,
+            _observer: observer,
+        });
+        if let Err(err) = view.clone().on_show().await {
+            let overlay = {
+                let mut overlays = self.overlays.lock()?;
+                overlays
+                    .iter()
+                    .position(|overlay| overlay.id == id)
+                    .map(|index| overlays.remove(index))
+            };
+            view.unsubscribe()?;
+            if let Some(overlay) = overlay {
+                self.element.remove_child(&overlay.element)?;
+            }
+            return Err(err);
+        }
+
+        match receiver.recv().await? {
+            Some(value) => value
+                .downcast::<T>()
+                .map(|value| *value)
+                .map_err(|_| error!("Overlay: unexpected result type")),
+            None => Err(Error::OverlayCancelled),
+        }
+    }
+
+    /// Dismisses the overlay presenting the view, resolving
+    /// its [`OverlayStack::present`] with the supplied value.
+    pub async fn dismiss<T: 'static>(
+        self: &Arc<Self>,
+        view: &Arc<dyn View>,
+        value: T,
+    ) -> Result<bool> {
+        match self.find(view)? {
+            Some(id) => self.remove(id, Some(Box::new(value)), false).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Closes the overlay presenting the view without a value.
+    /// Returns `false` if the view refused eviction.
+    pub async fn cancel(self: &Arc<Self>, view: &Arc<dyn View>) -> Result<bool> {
+        match self.find(view)? {
+            Some(id) => self.remove(id, None, true).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Closes the topmost overlay without a value.
+    pub async fn cancel_top(self: &Arc<Self>) -> Result<bool> {
+        let id = self.overlays.lock()?.last().map(|overlay| overlay.id);
+        match id {
+            Some(id) => self.remove(id, None, true).await,
+            None => Ok(false),
+        }
+    }
+
+    pub fn top(&self) -> Result<Option<Arc<dyn View>>> {
+        Ok(self
+            .overlays
+            .lock()?
+            .last()
+            .map(|overlay| overlay.view.clone()))
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.overlays.lock()?.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.overlays.lock()?.is_empty())
+    }
+
+    fn find(&self, view: &Arc<dyn View>) -> Result<Option<Id>> {
+        Ok(self
+            .overlays
+            .lock()?
+            .iter()
+            .find(|overlay| same_view(&overlay.view, view))
+            .map(|overlay| overlay.id))
+    }
+
+    async fn remove(self: &Arc<Self>, id: Id, result: OverlayResult, query: bool) -> Result<bool> {
+        let view = self
+            .overlays
+            .lock()?
+            .iter()
+            .find(|overlay| overlay.id == id)
+            .map(|overlay| overlay.view.clone());
+        let view = match view {
+            Some(view) => view,
+            None => return Ok(false),
+        };
+        if query && !query_eviction(&view).await? {
+            return Ok(false);
+        }
+
+        let (overlay, top) = {
+            let mut overlays = self.overlays.lock()?;
+            match overlays.iter().position(|overlay| overlay.id == id) {
+                Some(index) => {
+                    let top = index + 1 == overlays.len();
+                    (overlays.remove(index), top)
+                }
+                None => return Ok(false),
+            }
+        };
+
+        self.evict_overlay(&overlay).await?;
+        overlay.sender.try_send(result)?;
+        if top {
+            self.focus_top().await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Evicts the view of a removed overlay, wherever it was in the stack.
+    async fn evict_overlay(self: &Arc<Self>, overlay: &Overlay) -> Result<()> {
+        let view = &overlay.view;
+        view.clone().on_hide().await?;
+        view.unsubscribe()?;
+        if let Some(module) = view.module() {
+            module.evict_overlay(self, view.clone()).await?;
+        }
+        Progress::abort(view);
+        self.element.remove_child(&overlay.element)?;
+        view.clone().evict().await?;
+        View::drop(view.as_ref());
+        Ok(())
+    }
+
+    async fn focus_top(&self) -> Result<()> {
+        if let Some(top) = self.top()? {
+            top.on_focus_return().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the overlay stack, appending it to the document body on first use.
+pub fn overlays() -> Result<Arc<OverlayStack>> {
+    unsafe {
+        if let Some(overlays) = OVERLAYS.as_ref() {
+            return Ok(overlays.clone());
+        }
+        let overlays = OverlayStack::try_new()?;
+        OVERLAYS = Some(overlays.clone());
+        Ok(overlays)
+    }
+}
+
+pub fn global() -> Option<Arc<OverlayStack>> {
+    unsafe { OVERLAYS.clone() }
+}
+
+/// Dismisses the overlay presenting the view (see [`OverlayStack::dismiss`]).
+pub async fn dismiss<T: 'static>(view: &Arc<dyn View>, value: T) -> Result<bool> {
+    match global() {
+        Some(overlays) => overlays.dismiss(view, value).await,
+        None => Ok(false),
+    }
+}
@@ -2,7 +2,7 @@ pub use std::sync::{Arc, Mutex};
 // pub use async_std::sync::RwLock;
 pub use crate::control::{Control, ElementBindingContext};
 pub use crate::controls::helper::FieldHelper;
-pub use crate::form::{FormData, FormDataValue, FormHandler};
+pub use crate::form::{FormData, FormDataField, FormDataValue, FormHandler};
 pub use crate::theme::*;
 pub use crate::{document, window};
 pub use std::cell::RefCell;
@@ -45,7 +45,7 @@ pub use crate::popup_menu::PopupMenu;
 pub use crate::progress::*;
 pub use crate::qrcode;
 pub use crate::view;
-pub use crate::view::{Container, ContainerStack, Evict};
+pub use crate::view::{Container, ContainerStack, Evict, TabContainer};
 pub use crate::workspace;
 pub use crate::{
     async_trait, async_trait_with_send, async_trait_without_send, workflow_async_trait,
@@ -61,6 +61,7 @@ pub use workflow_core::id::Id;
 pub use crate::application::global as application;
 
 pub use workflow_ux_macros::declare_module;
+pub use workflow_ux_macros::FormModel;
 pub use workflow_ux_macros::Module;
 
 pub type CallbackFn<E> = Box<dyn FnMut(E) -> crate::result::Result<()>>;
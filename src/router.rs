@@ -0,0 +1,497 @@
+use crate::error::{error, Error};
+use crate::module::ModuleInterface;
+use crate::prelude::*;
+use crate::result::Result;
+use crate::transition::Direction;
+use crate::view::{Container, View};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+use workflow_wasm::callback::CallbackMap;
+use workflow_wasm::prelude::callback;
+
+static mut ROUTER: Option<Arc<Router>> = None;
+
+/// Attribute recording the route path on the element of a routed view.
+const ROUTE_ATTRIBUTE: &str = "data-route";
+
+/// Type of a route parameter declared as `:name<type>` in a route pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    String,
+    U64,
+    I64,
+    F64,
+    Bool,
+}
+
+impl FromStr for ParamKind {
+    type Err = Error;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let kind = match s {
+            "" | "str" | "string" => Self::String,
+            "u64" => Self::U64,
+            "i64" => Self::I64,
+            "f64" => Self::F64,
+            "bool" => Self::Bool,
+            _ => return Err(error!("Router: unsupported parameter type `{}`", s)),
+        };
+        Ok(kind)
+    }
+}
+
+impl ParamKind {
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            Self::String => !value.is_empty(),
+            Self::U64 => value.parse::<u64>().is_ok(),
+            Self::I64 => value.parse::<i64>().is_ok(),
+            Self::F64 => value.parse::<f64>().is_ok(),
+            Self::Bool => value.parse::<bool>().is_ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Static(String),
+    Param(String, ParamKind),
+    Wildcard(String),
+}
+
+impl FromStr for Segment {
+    type Err = Error;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(name) = s.strip_prefix('*') {
+            Ok(Segment::Wildcard(name.to_string()))
+        } else if let Some(param) = s.strip_prefix(':') {
+            match param.split_once('<') {
+                Some((name, kind)) => {
+                    let kind = kind
+                        .strip_suffix('>')
+                        .ok_or_else(|| error!("Router: invalid parameter `{}`", s))?;
+                    Ok(Segment::Param(name.to_string(), kind.parse()?))
+                }
+                None => Ok(Segment::Param(param.to_string(), ParamKind::String)),
+            }
+        } else {
+            Ok(Segment::Static(s.to_string()))
+        }
+    }
+}
+
+/// Parameters extracted from the path and the query string of a matched route.
+#[derive(Debug, Clone, Default)]
+pub struct Params {
+    path: String,
+    values: BTreeMap<String, String>,
+    query: BTreeMap<String, String>,
+}
+
+impl Params {
+    /// Path the parameters were extracted from (without the router base).
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|v| v.as_str())
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<T> {
+        let value = self
+            .get_str(name)
+            .ok_or_else(|| error!("Router: missing route parameter `{}`", name))?;
+        value
+            .parse::<T>()
+            .map_err(|_| error!("Router: invalid value `{}` for parameter `{}`", value, name))
+    }
+
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.get(name).map(|v| v.as_str())
+    }
+}
+
+/// Path pattern mapped to the module producing the view.
+///
+/// Patterns are `/` separated segments, where `:name` is a string
+/// parameter, `:name<u64>` (`i64`, `f64`, `bool`) is a typed parameter
+/// and `*name` captures the remainder of the path.
+pub struct Route {
+    pattern: String,
+    segments: Vec<Segment>,
+    module: Arc<dyn ModuleInterface>,
+}
+
+impl Route {
+    pub fn try_new(pattern: &str, module: Arc<dyn ModuleInterface>) -> Result<Route> {
+        let segments = split(pattern)
+            .map(Segment::from_str)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Route {
+            pattern: pattern.to_string(),
+            segments,
+            module,
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn module(&self) -> Arc<dyn ModuleInterface> {
+        self.module.clone()
+    }
+
+    fn matches(&self, path: &str) -> Option<BTreeMap<String, String>> {
+        match_segments(&self.segments, path)
+    }
+}
+
+fn match_segments(segments: &[Segment], path: &str) -> Option<BTreeMap<String, String>> {
+    let parts = split(path).collect::<Vec<_>>();
+    let mut values = BTreeMap::new();
+    for (index, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Wildcard(name) => {
+                values.insert(name.clone(), parts.get(index..)?.join("/"));
+                return Some(values);
+            }
+            Segment::Static(s) => {
+                if *parts.get(index)? != s.as_str() {
+                    return None;
+                }
+            }
+            Segment::Param(name, kind) => {
+                let value = decode(parts.get(index)?);
+                if !kind.accepts(&value) {
+                    return None;
+                }
+                values.insert(name.clone(), value);
+            }
+        }
+    }
+
+    (parts.len() == segments.len()).then_some(values)
+}
+
+/// Strips the router base from `path` if it is a prefix ending at a segment boundary
+fn strip_base<'p>(base: &str, path: &'p str) -> &'p str {
+    match path.strip_prefix(base) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        _ => path,
+    }
+}
+
+fn split(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|s| !s.is_empty())
+}
+
+fn decode(value: &str) -> String {
+    js_sys::decode_uri_component(value)
+        .map(String::from)
+        .unwrap_or_else(|_| value.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryAction {
+    Push,
+    Replace,
+    None,
+}
+
+/// Maps URL paths to module views loaded into a [`Container`], keeping
+/// the browser History API in sync with the displayed view.
+pub struct Router {
+    container: Arc<Container>,
+    base: String,
+    routes: RwLock<Vec<Arc<Route>>>,
+    pending: Mutex<Option<(String, HistoryAction)>>,
+    current: Mutex<Option<String>>,
+    index: Mutex<u32>,
+    started: Mutex<bool>,
+    callbacks: CallbackMap,
+}
+
+unsafe impl Send for Router {}
+unsafe impl Sync for Router {}
+
+impl Router {
+    /// Creates the global router driving the supplied container.
+    /// `base` is the path prefix the application is served from.
+    pub fn try_init(container: Arc<Container>, base: Option<&str>) -> Result<Arc<Router>> {
+        let base = base.unwrap_or("").trim_end_matches('/').to_string();
+        let router = Arc::new(Router {
+            container,
+            base,
+            routes: RwLock::new(Vec::new()),
+            pending: Mutex::new(None),
+            current: Mutex::new(None),
+            index: Mutex::new(0),
+            started: Mutex::new(false),
+            callbacks: CallbackMap::new(),
+        });
+
+        unsafe {
+            ROUTER = Some(router.clone());
+        }
+
+        Ok(router)
+    }
+
+    pub fn container(&self) -> Arc<Container> {
+        self.container.clone()
+    }
+
+    /// Registers a route pattern handled by the supplied module
+    /// (see [`ModuleInterface::route`]).
+    pub fn register(&self, pattern: &str, module: Arc<dyn ModuleInterface>) -> Result<()> {
+        let route = Route::try_new(pattern, module)?;
+        self.routes.write()?.push(Arc::new(route));
+        Ok(())
+    }
+
+    /// Path of the currently displayed route, if the view was loaded by the router.
+    pub fn current(&self) -> Result<Option<String>> {
+        Ok(self.current.lock()?.clone())
+    }
+
+    pub fn resolve(&self, path: &str) -> Result<Option<(Arc<Route>, Params)>> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let query = url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect::<BTreeMap<_, _>>();
+        for route in self.routes.read()?.iter() {
+            if let Some(values) = route.matches(path) {
+                let params = Params {
+                    path: path.to_string(),
+                    values,
+                    query,
+                };
+                return Ok(Some((route.clone(), params)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Loads the view for the current location and starts listening
+    /// to `popstate` events.  Must be called after module registration.
+    pub async fn start(self: &Arc<Self>) -> Result<bool> {
+        {
+            let mut started = self.started.lock()?;
+            if *started {
+                return Err(error!("Router: already started"));
+            }
+            *started = true;
+        }
+
+        let this = self.clone();
+        let callback = callback!(move |event: web_sys::PopStateEvent| -> Result<()> {
+            let this = this.clone();
+            let index = event.state().as_f64().map(|index| index as u32);
+            workflow_core::task::wasm::dispatch(async move {
+                if let Err(err) = this.on_popstate(index).await {
+                    log_error!("Router: popstate failure: {}", err);
+                }
+            });
+            Ok(())
+        });
+        window().add_event_listener_with_callback("popstate", callback.as_ref())?;
+        self.callbacks.retain(callback)?;
+
+        let path = self.location_path()?;
+        self.load(&path, HistoryAction::Replace, Direction::Forward)
+            .await
+    }
+
+    /// Loads the view matching `path` and pushes it to the browser history.
+    pub async fn navigate(self: &Arc<Self>, path: &str) -> Result<bool> {
+        self.load(path, HistoryAction::Push, Direction::Forward)
+            .await
+    }
+
+    /// Loads the view matching `path` replacing the current history entry.
+    pub async fn redirect(self: &Arc<Self>, path: &str) -> Result<bool> {
+        self.load(path, HistoryAction::Replace, Direction::Forward)
+            .await
+    }
+
+    async fn on_popstate(self: &Arc<Self>, index: Option<u32>) -> Result<()> {
+        let direction = match index {
+            Some(index) if index < *self.index.lock()? => Direction::Back,
+            _ => Direction::Forward,
+        };
+        if let Some(index) = index {
+            *self.index.lock()? = index;
+        }
+        let path = self.location_path()?;
+        self.load(&path, HistoryAction::None, direction).await?;
+        Ok(())
+    }
+
+    async fn load(
+        self: &Arc<Self>,
+        path: &str,
+        action: HistoryAction,
+        direction: Direction,
+    ) -> Result<bool> {
+        let (route, params) = match self.resolve(path)? {
+            Some(resolved) => resolved,
+            None => {
+                log_warning!("Router: no route for `{}`", path);
+                return Ok(false);
+            }
+        };
+
+        // resolve the view first so that a failing route leaves the current view in place
+        let view = route
+            .module()
+            .route(route.pattern(), &params)
+            .await?
+            .ok_or_else(|| error!("Router: route `{}` produced no view", route.pattern()))?;
+
+        *self.pending.lock()? = Some((path.to_string(), action));
+        let (transition, _) = self.container.transition()?;
+        let loaded = self.container.load(view, transition, direction).await;
+        if !matches!(loaded, Ok(Some(_))) {
+            self.pending.lock()?.take();
+        }
+        if loaded?.is_none() {
+            if action == HistoryAction::None {
+                self.restore_location()?;
+            }
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    fn location_path(&self) -> Result<String> {
+        let location = crate::application::global()?.location();
+        let path = location.path();
+        let path = strip_base(&self.base, path);
+        Ok(match location.query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path.to_string(),
+        })
+    }
+
+    /// Re-pushes the displayed route after a cancelled `popstate` navigation
+    /// so the location matches the view that stayed in place.
+    fn restore_location(&self) -> Result<()> {
+        if let Some(path) = self.current()? {
+            let index = {
+                let mut index = self.index.lock()?;
+                *index += 1;
+                *index
+            };
+            let url = format!("{}{}", self.base, path);
+            window()
+                .history()?
+                .push_state_with_url(&JsValue::from(index), "", Some(&url))?;
+        }
+        Ok(())
+    }
+
+    /// Invoked by the container once the view swap completes.  Views loaded
+    /// directly into the container (bypassing the router) update the location
+    /// if they were previously produced by a route, otherwise they leave the
+    /// router without a current route.
+    fn commit(&self, view: &Arc<dyn View>) -> Result<()> {
+        let element = view.element();
+        let (path, action) = match self.pending.lock()?.take() {
+            Some(pending) => pending,
+            None => match element.get_attribute(ROUTE_ATTRIBUTE) {
+                Some(path) if self.current()?.as_ref() != Some(&path) => {
+                    (path, HistoryAction::Push)
+                }
+                Some(_) => return Ok(()),
+                None => {
+                    *self.current.lock()? = None;
+                    return Ok(());
+                }
+            },
+        };
+
+        let url = format!("{}{}", self.base, path);
+        let history = window().history()?;
+        match action {
+            HistoryAction::Push => {
+                let index = {
+                    let mut index = self.index.lock()?;
+                    *index += 1;
+                    *index
+                };
+                history.push_state_with_url(&JsValue::from(index), "", Some(&url))?;
+            }
+            HistoryAction::Replace => {
+                let index = *self.index.lock()?;
+                history.replace_state_with_url(&JsValue::from(index), "", Some(&url))?;
+            }
+            HistoryAction::None => {}
+        }
+
+        element.set_attribute(ROUTE_ATTRIBUTE, &path)?;
+        *self.current.lock()? = Some(path);
+        Ok(())
+    }
+}
+
+pub fn global() -> Option<Arc<Router>> {
+    unsafe { ROUTER.clone() }
+}
+
+/// Keeps the router in sync with the view loaded into the router
+/// container, whether it was loaded by the router or by the container.
+pub fn commit(container: &Arc<Container>, view: &Arc<dyn View>) -> Result<()> {
+    if let Some(router) = global() {
+        if Arc::ptr_eq(&router.container, container) {
+            router.commit(view)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn segments(pattern: &str) -> Vec<Segment> {
+        split(pattern)
+            .map(Segment::from_str)
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn match_static_and_typed_params() {
+        let route = segments("/users/:id<u64>/posts/:slug");
+        let values = match_segments(&route, "/users/42/posts/hello").unwrap();
+        assert_eq!(values.get("id").unwrap(), "42");
+        assert_eq!(values.get("slug").unwrap(), "hello");
+        assert!(match_segments(&route, "/users/abc/posts/hello").is_none());
+        assert!(match_segments(&route, "/users/42/posts").is_none());
+        assert!(match_segments(&route, "/users/42/posts/hello/more").is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn match_wildcard() {
+        let route = segments("/files/*path");
+        let values = match_segments(&route, "/files/a/b/c.txt").unwrap();
+        assert_eq!(values.get("path").unwrap(), "a/b/c.txt");
+    }
+
+    #[wasm_bindgen_test]
+    fn reject_unknown_param_kind() {
+        assert!(Segment::from_str(":id<u8>").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn strip_base_at_segment_boundary() {
+        assert_eq!(strip_base("/app", "/app/users"), "/users");
+        assert_eq!(strip_base("/app", "/app"), "");
+        assert_eq!(strip_base("/app", "/application"), "/application");
+        assert_eq!(strip_base("", "/users"), "/users");
+    }
+}
@@ -0,0 +1,247 @@
+//!
+//! JSON Schema export of layouts (see the generated `schema()` function
+//! of the layout macros).  The schema describes the JSON representation
+//! of the [`FormData`] collected from the layout, i.e.
+//! `{"id":null,"values":{"name":{"type":"string","value":"..."}}}`, each
+//! property being tagged with its `FormDataValue` type.
+//!
+
+use crate::prelude::*;
+use crate::validation::Rule;
+pub use serde_json::{Map, Value};
+
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Decimal number entered as text
+const NUMERIC_PATTERN: &str = r"^-?[0-9]*\.?[0-9]*$";
+
+/// Schema of a single layout field
+pub struct FieldSchema {
+    /// `FormDataValue` type of the field value (i.e. `string`, `bool`, `json`)
+    tag: &'static str,
+    schema: Map<String, Value>,
+    required: bool,
+}
+
+impl FieldSchema {
+    pub fn new(kind: &str) -> Self {
+        let tag = match kind {
+            "string" => "string",
+            "number" => "f64",
+            "boolean" => "bool",
+            _ => "json",
+        };
+        let mut schema = Map::new();
+        schema.insert("type".into(), kind.into());
+        Self {
+            tag,
+            schema,
+            required: false,
+        }
+    }
+
+    pub fn string() -> Self {
+        Self::new("string")
+    }
+
+    pub fn number() -> Self {
+        Self::new("number")
+    }
+
+    /// Number kept as text, as entered in `Input` controls of type `number`
+    pub fn numeric() -> Self {
+        Self::string().with("pattern", NUMERIC_PATTERN)
+    }
+
+    pub fn boolean() -> Self {
+        Self::new("boolean")
+    }
+
+    /// String restricted to the values of `E`, described by their docs
+    pub fn enumeration<E: EnumTrait<E>>() -> Self {
+        let list = E::list();
+        let mut field = Self::string();
+        field.schema.insert(
+            "enum".into(),
+            list.iter().map(|item| Value::from(item.as_str())).collect(),
+        );
+        field.schema.insert(
+            "x-enum-descriptions".into(),
+            list.iter().map(|item| Value::from(item.descr())).collect(),
+        );
+        field
+    }
+
+    /// JSON array of `items`
+    pub fn array(items: FieldSchema) -> Self {
+        let mut field = Self::new("array");
+        field.schema.insert("items".into(), items.into_value());
+        field
+    }
+
+    /// Values of a nested layout stored as a JSON object, `schema` being the
+    /// schema of its struct (see [`FormData::add_object_json()`])
+    pub fn object(schema: Value) -> Self {
+        let mut field = Self::new("object");
+        if let Some(values) = schema
+            .pointer("/properties/values")
+            .and_then(Value::as_object)
+        {
+            field.schema.extend(values.clone());
+        }
+        for key in ["title", "x-i18n-key"] {
+            if let Some(value) = schema.get(key) {
+                field.schema.insert(key.into(), value.clone());
+            }
+        }
+        field
+    }
+
+    /// Sets the translated title; the untranslated text is the i18n key
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.schema.insert("title".into(), i18n(title).into());
+        self.schema.insert("x-i18n-key".into(), title.into());
+        self
+    }
+
+    /// Uses the field docs as the description, the first line being
+    /// the title unless one is already set
+    pub fn with_docs(mut self, docs: &[&str]) -> Self {
+        let lines: Vec<&str> = docs.iter().map(|line| line.trim()).collect();
+        if lines.is_empty() {
+            return self;
+        }
+        if !self.schema.contains_key("title") {
+            self = self.with_title(lines[0]);
+        }
+        self.schema
+            .insert("description".into(), lines.join("\n").trim().into());
+        self
+    }
+
+    /// Sets the constraints of the validation `rules`
+    pub fn with_rules(mut self, rules: &[Rule]) -> Self {
+        for rule in rules {
+            let (key, value) = match rule {
+                Rule::Required => {
+                    self.required = true;
+                    continue;
+                }
+                Rule::MinLen(len) => ("minLength", Value::from(*len)),
+                Rule::MaxLen(len) => ("maxLength", Value::from(*len)),
+                Rule::Pattern(pattern) => ("pattern", Value::from(pattern.as_str())),
+                // bounds of numbers kept as text are annotations only
+                Rule::Min(min) if self.is_string() => ("x-minimum", Value::from(*min)),
+                Rule::Max(max) if self.is_string() => ("x-maximum", Value::from(*max)),
+                Rule::Min(min) => ("minimum", Value::from(*min)),
+                Rule::Max(max) => ("maximum", Value::from(*max)),
+                Rule::Email => ("format", Value::from("email")),
+                // not expressible in JSON Schema
+                Rule::Custom(_) => continue,
+            };
+            self.schema.insert(key.into(), value);
+        }
+        self
+    }
+
+    /// Sets any other schema keyword
+    pub fn with<V: Into<Value>>(mut self, key: &str, value: V) -> Self {
+        self.schema.insert(key.into(), value.into());
+        self
+    }
+
+    fn is_string(&self) -> bool {
+        self.schema.get("type") == Some(&Value::from("string"))
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Schema of the value alone
+    pub fn into_value(self) -> Value {
+        Value::Object(self.schema)
+    }
+
+    /// Schema of the tagged value, as stored in [`FormData`]
+    pub fn into_tagged_value(self) -> Value {
+        let mut tag = Map::new();
+        tag.insert("const".into(), self.tag.into());
+        let mut properties = Map::new();
+        properties.insert("type".into(), Value::Object(tag));
+        properties.insert("value".into(), self.into_value());
+        let mut schema = Map::new();
+        schema.insert("type".into(), "object".into());
+        schema.insert("properties".into(), Value::Object(properties));
+        schema.insert("required".into(), vec!["type", "value"].into());
+        Value::Object(schema)
+    }
+}
+
+/// Schema of the [`FormData`] of a layout struct, with one property per field
+pub struct LayoutSchema {
+    title: Option<String>,
+    properties: Map<String, Value>,
+    required: Vec<Value>,
+}
+
+impl LayoutSchema {
+    pub fn new(title: Option<&str>) -> Self {
+        Self {
+            title: title.filter(|title| !title.is_empty()).map(String::from),
+            properties: Map::new(),
+            required: Vec::new(),
+        }
+    }
+
+    pub fn field(mut self, name: &str, field: FieldSchema) -> Self {
+        if field.is_required() {
+            self.required.push(name.into());
+        }
+        self.properties
+            .insert(name.into(), field.into_tagged_value());
+        self
+    }
+
+    /// Adds the fields of a nested layout whose values are stored alongside
+    /// the fields of this one (i.e. the stages of a `#[stage]` layout)
+    pub fn merge(mut self, schema: Value) -> Self {
+        let values = match schema.pointer("/properties/values") {
+            Some(values) => values,
+            None => return self,
+        };
+        if let Some(properties) = values.get("properties").and_then(Value::as_object) {
+            self.properties.extend(properties.clone());
+        }
+        if let Some(required) = values.get("required").and_then(Value::as_array) {
+            self.required.extend(required.iter().cloned());
+        }
+        self
+    }
+
+    pub fn build(self) -> Value {
+        let mut values = Map::new();
+        values.insert("type".into(), "object".into());
+        values.insert("properties".into(), Value::Object(self.properties));
+        if !self.required.is_empty() {
+            values.insert("required".into(), Value::Array(self.required));
+        }
+        let mut properties = Map::new();
+        properties.insert(
+            "id".into(),
+            serde_json::json!({ "type": ["string", "null"] }),
+        );
+        properties.insert("values".into(), Value::Object(values));
+
+        let mut schema = Map::new();
+        schema.insert("$schema".into(), JSON_SCHEMA_DIALECT.into());
+        schema.insert("type".into(), "object".into());
+        if let Some(title) = self.title {
+            schema.insert("title".into(), i18n(&title).into());
+            schema.insert("x-i18n-key".into(), title.into());
+        }
+        schema.insert("properties".into(), Value::Object(properties));
+        schema.insert("required".into(), vec!["values"].into());
+        Value::Object(schema)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::error;
+use crate::form::FormData;
+use crate::module::{self, ModuleInterface};
+use crate::result::Result;
+use crate::utils::local_storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Serializable snapshot of a view produced by [`View::snapshot`](crate::view::View::snapshot),
+/// persisted by a [`Container`](crate::view::Container) across page reloads.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewState {
+    /// Identifies the view within its module (see [`ModuleInterface::restore`]).
+    pub key: String,
+    /// Vertical scroll position of the container.
+    pub scroll_top: i32,
+    /// Values of the form displayed by the view.
+    pub data: Option<FormData>,
+    /// Selected tab, stage or list item.
+    pub selected: Option<String>,
+    /// Any additional view specific values.
+    pub values: BTreeMap<String, String>,
+}
+
+impl ViewState {
+    pub fn new<T: Into<String>>(key: T) -> Self {
+        ViewState {
+            key: key.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_data(mut self, data: FormData) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn with_selected<T: Into<String>>(mut self, selected: T) -> Self {
+        self.selected = Some(selected.into());
+        self
+    }
+
+    pub fn with_value<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.values.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|v| v.as_str())
+    }
+}
+
+/// View state stored as JSON together with the name of the module owning the view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedState {
+    pub module: String,
+    pub state: ViewState,
+}
+
+impl PersistedState {
+    /// Loads the state stored under the `local_storage()` key.
+    pub fn load(storage_key: &str) -> Result<Option<PersistedState>> {
+        let value = match local_storage().get_item(storage_key)? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        Ok(Some(serde_json::from_str(&value)?))
+    }
+
+    pub fn store(&self, storage_key: &str) -> Result<()> {
+        local_storage().set_item(storage_key, &serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn clear(storage_key: &str) -> Result<()> {
+        local_storage().remove_item(storage_key)?;
+        Ok(())
+    }
+
+    pub fn module(&self) -> Result<Arc<dyn ModuleInterface>> {
+        module::get_module(&self.module)
+            .map(|module| module.iface.clone())
+            .ok_or_else(|| error!("ViewState: unknown module `{}`", self.module))
+    }
+}
@@ -0,0 +1,184 @@
+//!
+//! Stepper header showing the stages of a multi-stage form
+//! (see [`FormStages`](crate::form::FormStages) and the `#[stage]` layout macro).
+//!
+
+use crate::icon::Icon;
+use crate::prelude::*;
+use crate::result::Result;
+use std::collections::BTreeSet;
+use workflow_wasm::callback::CallbackMap;
+use workflow_wasm::prelude::callback;
+
+pub static CSS: &str = include_str!("stepper.css");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepState {
+    Pending,
+    Current,
+    Completed,
+    Error,
+}
+
+impl StepState {
+    fn class(&self) -> &'static str {
+        match self {
+            StepState::Pending => "pending",
+            StepState::Current => "current",
+            StepState::Completed => "completed",
+            StepState::Error => "error",
+        }
+    }
+}
+
+pub struct Step {
+    pub title: String,
+    pub icon: Option<Icon>,
+    pub state: StepState,
+}
+
+impl Step {
+    pub fn new<T: Into<String>>(title: T) -> Self {
+        Self {
+            title: title.into(),
+            icon: None,
+            state: StepState::Pending,
+        }
+    }
+
+    pub fn with_icon(mut self, icon: Option<Icon>) -> Self {
+        self.icon = icon;
+        self
+    }
+
+    pub fn with_state(mut self, state: StepState) -> Self {
+        self.state = state;
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct Stepper {
+    element: Element,
+    navigable: Arc<Mutex<bool>>,
+    /// indexes of the steps flagged by [`Stepper::mark_error()`]
+    errors: Arc<Mutex<BTreeSet<usize>>>,
+    select_cb: Arc<Mutex<Option<CallbackFn<usize>>>>,
+    callbacks: CallbackMap,
+}
+
+unsafe impl Send for Stepper {}
+unsafe impl Sync for Stepper {}
+
+impl Stepper {
+    pub fn try_new() -> Result<Self> {
+        let stepper = Self {
+            element: create_el("ol.workflow-stepper", vec![], None)?,
+            navigable: Arc::new(Mutex::new(false)),
+            errors: Arc::new(Mutex::new(BTreeSet::new())),
+            select_cb: Arc::new(Mutex::new(None)),
+            callbacks: CallbackMap::new(),
+        };
+        stepper.init()?;
+        Ok(stepper)
+    }
+
+    fn init(&self) -> Result<()> {
+        let navigable = self.navigable.clone();
+        let select_cb = self.select_cb.clone();
+        let callback = callback!(move |event: web_sys::MouseEvent| -> Result<()> {
+            if !*navigable.lock()? {
+                return Ok(());
+            }
+            let step = match event
+                .target()
+                .and_then(|t| t.dyn_into::<Element>().ok())
+                .map(|target| target.closest(".workflow-stepper-step.completed"))
+                .transpose()?
+                .flatten()
+            {
+                Some(step) => step,
+                None => return Ok(()),
+            };
+            let index = step
+                .get_attribute("data-index")
+                .and_then(|index| index.parse::<usize>().ok());
+            if let (Some(index), Some(cb)) = (index, select_cb.lock()?.as_mut()) {
+                cb(index)?;
+            }
+            Ok(())
+        });
+        self.element
+            .add_event_listener_with_callback("click", callback.as_ref())?;
+        self.callbacks.retain(callback)?;
+        Ok(())
+    }
+
+    pub fn element(&self) -> Element {
+        self.element.clone()
+    }
+
+    /// Allows returning to a completed step by clicking it
+    /// (reported via [`Stepper::on_select()`]).
+    pub fn set_navigable(&self, navigable: bool) -> Result<()> {
+        *self.navigable.lock()? = navigable;
+        self.element
+            .class_list()
+            .toggle_with_force("navigable", navigable)?;
+        Ok(())
+    }
+
+    pub fn on_select(&self, callback: CallbackFn<usize>) -> Result<()> {
+        *self.select_cb.lock()? = Some(callback);
+        Ok(())
+    }
+
+    pub fn set_steps(&self, steps: Vec<Step>) -> Result<()> {
+        self.element.set_inner_html("");
+        let errors = self.errors.lock()?.clone();
+        for (index, step) in steps.into_iter().enumerate() {
+            let el = create_el(
+                &format!("li.workflow-stepper-step.{}", step.state.class()),
+                vec![("data-index", &index.to_string())],
+                None,
+            )?;
+            if errors.contains(&index) {
+                el.class_list().add_1(StepState::Error.class())?;
+            }
+            let marker = create_el("span.marker", vec![], None)?;
+            match &step.icon {
+                Some(icon) => {
+                    marker.append_child(&icon.element()?)?;
+                }
+                None => marker.set_text_content(Some(&(index + 1).to_string())),
+            }
+            el.append_child(&marker)?;
+            let title = create_el("span.title", vec![], None)?;
+            title.set_text_content(Some(&step.title));
+            el.append_child(&title)?;
+            self.element.append_child(&el)?;
+        }
+        Ok(())
+    }
+
+    /// Flags the step at `index` (i.e. after a failed validation)
+    /// until it is cleared by [`Stepper::clear_error()`].
+    pub fn mark_error(&self, index: usize) -> Result<()> {
+        self.errors.lock()?.insert(index);
+        self.toggle_error(index, true)
+    }
+
+    pub fn clear_error(&self, index: usize) -> Result<()> {
+        self.errors.lock()?.remove(&index);
+        self.toggle_error(index, false)
+    }
+
+    fn toggle_error(&self, index: usize, error: bool) -> Result<()> {
+        let selector = format!(".workflow-stepper-step[data-index=\"{index}\"]");
+        if let Some(el) = self.element.query_selector(&selector)? {
+            el.class_list()
+                .toggle_with_force(StepState::Error.class(), error)?;
+        }
+        Ok(())
+    }
+}
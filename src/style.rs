@@ -10,9 +10,16 @@ impl ControlStyle {
     pub fn get() -> Vec<&'static str> {
         Vec::from([
             mnemonic::CSS,
+            repeater::CSS,
             crate::menu::CSS,
             crate::pagination::CSS,
             crate::dialog::CSS,
+            crate::layout::CSS,
+            crate::overlay::CSS,
+            crate::stepper::CSS,
+            crate::transition::CSS,
+            crate::validation::CSS,
+            crate::view::CSS,
             CSS,
         ])
     }
@@ -1,5 +1,6 @@
 use std::sync::{Arc, LockResult, Mutex, MutexGuard};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
 //use workflow_log::log_trace;
 use crate::result::Result;
 
@@ -108,6 +109,12 @@ impl FunctionDebounce {
 
         Ok(())
     }
+    /// Drops the pending invocation, if any
+    pub fn cancel(&self) -> Result<()> {
+        self.clear_timeout()?;
+        self.inner()?.interval = None;
+        Ok(())
+    }
     pub fn execute(&self) -> Result<()> {
         self.execute_(FunctionDebounceCallbackArgs::None)?;
         Ok(())
@@ -138,3 +145,23 @@ impl FunctionDebounce {
         Ok(())
     }
 }
+
+/// Resolves after `duration` milliseconds.
+pub async fn sleep(duration: u32) -> Result<()> {
+    let mut closure = None;
+    let mut timeout = None;
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let cb = Closure::<dyn FnMut() -> Result<()>>::new(move || -> Result<()> {
+            resolve.call0(&JsValue::UNDEFINED)?;
+            Ok(())
+        });
+        timeout = Some(set_timeout(&cb, duration));
+        closure = Some(cb);
+    });
+    if let Some(Err(err)) = timeout {
+        return Err(err.into());
+    }
+    JsFuture::from(promise).await?;
+    drop(closure);
+    Ok(())
+}
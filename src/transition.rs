@@ -0,0 +1,138 @@
+use crate::prelude::*;
+use crate::result::Result;
+use crate::task::sleep;
+
+pub static CSS: &str = include_str!("transition.css");
+
+/// Default duration (in milliseconds) of a view transition.
+pub const DEFAULT_DURATION: u32 = 250;
+
+/// Animation played by a [`Container`](crate::view::Container)
+/// when swapping one view for another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transition {
+    /// Replace the view immediately.
+    #[default]
+    None,
+    /// Fade the outgoing view out, then fade the incoming view in.
+    Fade,
+    /// Slide both views to the left (incoming view enters from the right).
+    SlideLeft,
+    /// Slide both views to the right (incoming view enters from the left).
+    SlideRight,
+    /// Fade the outgoing and the incoming views simultaneously.
+    CrossFade,
+}
+
+/// Navigation direction; back navigation plays the reverse transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Forward,
+    Back,
+}
+
+impl Transition {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Fade => "fade",
+            Self::SlideLeft => "slide-left",
+            Self::SlideRight => "slide-right",
+            Self::CrossFade => "cross-fade",
+        }
+    }
+
+    pub fn reverse(&self) -> Transition {
+        match self {
+            Self::SlideLeft => Self::SlideRight,
+            Self::SlideRight => Self::SlideLeft,
+            _ => *self,
+        }
+    }
+
+    pub fn resolve(&self, direction: Direction) -> Transition {
+        match direction {
+            Direction::Forward => *self,
+            Direction::Back => self.reverse(),
+        }
+    }
+
+    /// Returns `true` if outgoing and incoming views are animated at the same time.
+    pub fn is_concurrent(&self) -> bool {
+        matches!(self, Self::SlideLeft | Self::SlideRight | Self::CrossFade)
+    }
+
+    fn exit_class(&self) -> String {
+        format!("view-transition-{}-exit", self.name())
+    }
+
+    fn enter_class(&self) -> String {
+        format!("view-transition-{}-enter", self.name())
+    }
+
+    /// Plays the exit animation on the `outgoing` element.
+    pub async fn exit(&self, outgoing: &Element, duration: u32) -> Result<()> {
+        self.play(Some(outgoing), None, duration).await
+    }
+
+    /// Plays the enter animation on the `incoming` element.
+    pub async fn enter(&self, incoming: &Element, duration: u32) -> Result<()> {
+        self.play(None, Some(incoming), duration).await
+    }
+
+    /// Plays the exit animation on `outgoing` and the enter
+    /// animation on `incoming` simultaneously.
+    pub async fn play(
+        &self,
+        outgoing: Option<&Element>,
+        incoming: Option<&Element>,
+        duration: u32,
+    ) -> Result<()> {
+        if *self == Self::None || (outgoing.is_none() && incoming.is_none()) {
+            return Ok(());
+        }
+
+        let duration_str = format!("{duration}ms");
+        if let Some(el) = outgoing {
+            set_duration(el, Some(&duration_str))?;
+            el.class_list()
+                .add_2("view-transition-exit", &self.exit_class())?;
+        }
+        if let Some(el) = incoming {
+            set_duration(el, Some(&duration_str))?;
+            el.class_list()
+                .add_2("view-transition-enter", &self.enter_class())?;
+        }
+
+        sleep(duration).await?;
+
+        if let Some(el) = outgoing {
+            set_duration(el, None)?;
+            el.class_list()
+                .remove_2("view-transition-exit", &self.exit_class())?;
+        }
+        if let Some(el) = incoming {
+            set_duration(el, None)?;
+            el.class_list()
+                .remove_2("view-transition-enter", &self.enter_class())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn set_duration(el: &Element, duration: Option<&str>) -> Result<()> {
+    if let Some(el) = el.dyn_ref::<HtmlElement>() {
+        let style = el.style();
+        match duration {
+            Some(duration) => {
+                style.set_property("--workspace-view-transition-duration", duration)?
+            }
+            None => {
+                style.remove_property("--workspace-view-transition-duration")?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,513 @@
+//!
+//! Declarative field validation used by the `#[form]`, `#[section]` and
+//! other layout macros. Each control field may carry validation rules
+//! (`required`, `min_len`, `max_len`, `pattern`, `min`, `max`, `email`
+//! and `custom`) from which the macro generates
+//! [`DefaultFunctions::validate()`](crate::layout::DefaultFunctions::validate).
+//! Checks that need a round-trip (e.g. "is this username available") are
+//! declared with `async_validator = fn` (and optionally `debounce = ms`)
+//! and run as an [`AsyncValidator`].
+//!
+
+use crate::controls::prelude::*;
+use crate::error::error;
+use crate::layout::DefaultFunctions;
+use crate::prelude::*;
+use crate::result::Result;
+use crate::task::FunctionDebounce;
+use regex::Regex;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use workflow_core::channel::{oneshot, Sender};
+use workflow_core::task::wasm::dispatch;
+use workflow_wasm::callback::CallbackMap;
+use workflow_wasm::prelude::callback;
+
+pub static CSS: &str = include_str!("validation.css");
+
+/// Custom validator function; returns the error message if `value` is invalid.
+pub type CustomValidator = fn(&str) -> std::result::Result<(), String>;
+
+/// Regular expression of a [`Rule::Pattern`], validated by the layout
+/// macros at compile time and compiled once on first use.
+pub struct Pattern {
+    source: &'static str,
+    regex: OnceLock<Regex>,
+}
+
+impl Pattern {
+    pub const fn new(source: &'static str) -> Self {
+        Pattern {
+            source,
+            regex: OnceLock::new(),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.source
+    }
+
+    pub fn regex(&self) -> Result<&Regex> {
+        if let Some(regex) = self.regex.get() {
+            return Ok(regex);
+        }
+        let regex = Regex::new(self.source)
+            .map_err(|err| error!("Invalid validation pattern `{}`: {}", self.source, err))?;
+        Ok(self.regex.get_or_init(|| regex))
+    }
+}
+
+#[derive(Clone)]
+pub enum Rule {
+    Required,
+    MinLen(usize),
+    MaxLen(usize),
+    Pattern(&'static Pattern),
+    Min(f64),
+    Max(f64),
+    Email,
+    Custom(CustomValidator),
+}
+
+impl Rule {
+    /// Returns the (translated) message if `value` does not satisfy this rule.
+    /// Empty values only fail the [`Rule::Required`] rule.
+    pub fn check(&self, value: &str) -> Result<Option<String>> {
+        if value.trim().is_empty() {
+            return Ok(matches!(self, Rule::Required).then(|| i18n("This field is required")));
+        }
+
+        let message = match self {
+            Rule::Required => None,
+            Rule::MinLen(len) => (value.chars().count() < *len).then(|| {
+                i18n("Please enter at least [N] characters").replace("[N]", &len.to_string())
+            }),
+            Rule::MaxLen(len) => (value.chars().count() > *len).then(|| {
+                i18n("Please enter no more than [N] characters").replace("[N]", &len.to_string())
+            }),
+            Rule::Pattern(pattern) => (!pattern.regex()?.is_match(value))
+                .then(|| i18n("Please enter a value in the required format")),
+            Rule::Min(min) => match value.trim().parse::<f64>() {
+                Ok(number) if number >= *min => None,
+                Ok(_) => Some(
+                    i18n("Please enter a value of at least [N]").replace("[N]", &min.to_string()),
+                ),
+                Err(_) => Some(i18n("Please enter a number")),
+            },
+            Rule::Max(max) => match value.trim().parse::<f64>() {
+                Ok(number) if number <= *max => None,
+                Ok(_) => Some(
+                    i18n("Please enter a value no greater than [N]")
+                        .replace("[N]", &max.to_string()),
+                ),
+                Err(_) => Some(i18n("Please enter a number")),
+            },
+            Rule::Email => {
+                (!is_email(value.trim())).then(|| i18n("Please enter a valid email address"))
+            }
+            Rule::Custom(validator) => validator(value).err(),
+        };
+
+        Ok(message)
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((user, domain)) => {
+            !user.is_empty()
+                && !domain.contains('@')
+                && domain.split('.').count() > 1
+                && domain.split('.').all(|part| !part.is_empty())
+                && !value.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// Returns the message of the first rule `value` fails, if any.
+pub fn validate(value: &str, rules: &[Rule]) -> Result<Option<String>> {
+    for rule in rules.iter() {
+        if let Some(message) = rule.check(value)? {
+            return Ok(Some(message));
+        }
+    }
+    Ok(None)
+}
+
+/// Marks `element` as invalid and shows `message` right after it,
+/// or clears both when `message` is `None`.
+pub fn show_message(element: &Element, message: Option<&str>) -> Result<()> {
+    element
+        .class_list()
+        .toggle_with_force("invalid", message.is_some())?;
+    render_message(element, message, false)
+}
+
+/// Shows (or clears) the pending state of a control awaiting an [`AsyncValidator`].
+pub fn show_pending(element: &Element, pending: bool) -> Result<()> {
+    element.class_list().toggle_with_force("pending", pending)?;
+    if pending {
+        element.class_list().remove_1("invalid")?;
+        render_message(element, Some(&i18n("Checking...")), true)
+    } else {
+        render_message(element, None, false)
+    }
+}
+
+fn render_message(element: &Element, message: Option<&str>, pending: bool) -> Result<()> {
+    let existing = element
+        .next_element_sibling()
+        .filter(|el| el.class_list().contains("validation-message"));
+    match (existing, message) {
+        (Some(el), Some(message)) => {
+            el.set_text_content(Some(message));
+            el.class_list().toggle_with_force("pending", pending)?;
+        }
+        (Some(el), None) => el.remove(),
+        (None, Some(message)) => {
+            let el = create_el("div.validation-message", vec![], None)?;
+            el.set_text_content(Some(message));
+            el.class_list().toggle_with_force("pending", pending)?;
+            element.after_with_node_1(&el)?;
+        }
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+/// Controls that can be checked against validation [`Rule`]s.
+pub trait Validatable {
+    fn validation_value(&self) -> String;
+    fn validation_element(&self) -> Element;
+
+    fn validate_rules(&self, rules: &[Rule]) -> Result<bool> {
+        let message = validate(&self.validation_value(), rules)?;
+        show_message(&self.validation_element(), message.as_deref())?;
+        Ok(message.is_none())
+    }
+}
+
+/// Validates the layout rules and then waits for its pending async validators.
+pub async fn validate_layout<T>(layout: &T) -> Result<bool>
+where
+    T: DefaultFunctions + ?Sized,
+{
+    let valid = layout.validate()?;
+    match layout.async_validators() {
+        Some(validators) => Ok(validators.wait().await? && valid),
+        None => Ok(valid),
+    }
+}
+
+pub type AsyncValidatorFuture = Pin<Box<dyn Future<Output = std::result::Result<(), String>>>>;
+/// Async validator function; resolves to the error message if the value is invalid.
+pub type AsyncValidatorFn = Arc<dyn Fn(String) -> AsyncValidatorFuture>;
+
+#[derive(Default)]
+struct AsyncValidatorState {
+    generation: u64,
+    value: Option<String>,
+    pending: bool,
+    message: Option<String>,
+    /// set while the control is hidden, see [`AsyncValidator::set_active()`]
+    inactive: bool,
+    /// notified once the value is no longer pending
+    waiters: Vec<Sender<()>>,
+}
+
+impl AsyncValidatorState {
+    fn set_pending(&mut self, pending: bool) -> Result<()> {
+        self.pending = pending;
+        if !pending {
+            for waiter in self.waiters.drain(..) {
+                waiter.try_send(())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Inner {
+    element: Element,
+    value: Arc<dyn Fn() -> String>,
+    rules: Vec<Rule>,
+    validator: AsyncValidatorFn,
+    state: Mutex<AsyncValidatorState>,
+    debounce: Mutex<Option<FunctionDebounce>>,
+    callbacks: CallbackMap,
+}
+
+/// Runs an async check against a control each time its value changes.
+/// Checks are debounced and a check whose value has changed in the
+/// meantime is discarded once it resolves.
+#[derive(Clone)]
+pub struct AsyncValidator(Arc<Inner>);
+
+impl AsyncValidator {
+    /// Binds `validator` to `control`; it runs `delay` milliseconds after the
+    /// last change, provided the value satisfies the synchronous `rules`.
+    pub fn bind<C>(
+        control: &C,
+        rules: Vec<Rule>,
+        delay: u32,
+        validator: AsyncValidatorFn,
+    ) -> Result<Self>
+    where
+        C: Validatable + Clone + 'static,
+    {
+        let ctl = control.clone();
+        let this = Self(Arc::new(Inner {
+            element: control.validation_element(),
+            value: Arc::new(move || ctl.validation_value()),
+            rules,
+            validator,
+            state: Mutex::new(AsyncValidatorState::default()),
+            debounce: Mutex::new(None),
+            callbacks: CallbackMap::new(),
+        }));
+
+        let inner = Arc::downgrade(&this.0);
+        *this.0.debounce.lock()? = Some(FunctionDebounce::new_with_str(
+            delay,
+            Box::new(move |value: String| {
+                if let Some(inner) = inner.upgrade() {
+                    let validator = AsyncValidator(inner);
+                    dispatch(async move { validator.check(value).await });
+                }
+                Ok(())
+            }),
+        ));
+
+        let inner = Arc::downgrade(&this.0);
+        let callback = callback!(move |_event: web_sys::Event| -> Result<()> {
+            match inner.upgrade() {
+                Some(inner) => AsyncValidator(inner).schedule(),
+                None => Ok(()),
+            }
+        });
+        for event in ["changed", "change", "keyup"] {
+            this.0
+                .element
+                .add_event_listener_with_callback(event, callback.as_ref())?;
+        }
+        this.0.callbacks.retain(callback)?;
+
+        Ok(this)
+    }
+
+    pub fn is_pending(&self) -> Result<bool> {
+        Ok(self.0.state.lock()?.pending)
+    }
+
+    fn satisfies_rules(&self, value: &str) -> Result<bool> {
+        Ok(validate(value, &self.0.rules)?.is_none())
+    }
+
+    /// Inactive validators (i.e. of hidden controls) neither run nor fail
+    /// [`AsyncValidator::wait()`]; deactivating discards the pending check.
+    pub fn set_active(&self, active: bool) -> Result<()> {
+        let mut state = self.0.state.lock()?;
+        if state.inactive == !active {
+            return Ok(());
+        }
+        state.inactive = !active;
+        // the value is checked again once active
+        state.generation += 1;
+        state.value = None;
+        state.message = None;
+        state.set_pending(false)?;
+        show_pending(&self.0.element, false)
+    }
+
+    fn schedule(&self) -> Result<()> {
+        let value = (self.0.value)();
+        {
+            let mut state = self.0.state.lock()?;
+            if state.inactive || state.value.as_ref() == Some(&value) {
+                return Ok(());
+            }
+            // invalidates any check still in flight
+            state.generation += 1;
+            state.value = Some(value.clone());
+            state.message = None;
+            let pending = !value.trim().is_empty() && self.satisfies_rules(&value)?;
+            state.set_pending(pending)?;
+            if !pending {
+                return show_pending(&self.0.element, false);
+            }
+        }
+
+        show_pending(&self.0.element, true)?;
+        if let Some(debounce) = self.0.debounce.lock()?.as_ref() {
+            debounce.execute_with_str(value)?;
+        }
+        Ok(())
+    }
+
+    async fn check(&self, value: String) -> Result<()> {
+        let generation = {
+            let mut state = self.0.state.lock()?;
+            if state.value.as_ref() != Some(&value) {
+                return Ok(());
+            }
+            state.generation += 1;
+            state.pending = true;
+            state.generation
+        };
+
+        let result = (self.0.validator)(value).await;
+
+        let mut state = self.0.state.lock()?;
+        if state.generation != generation {
+            // the value has changed since this check started
+            return Ok(());
+        }
+        state.message = result.err();
+        state.set_pending(false)?;
+        show_pending(&self.0.element, false)?;
+        show_message(&self.0.element, state.message.as_deref())?;
+        Ok(())
+    }
+
+    /// Waits for the check of the current value, running it right away if
+    /// it has not been scheduled yet. Resolves to `false` if it failed.
+    pub async fn wait(&self) -> Result<bool> {
+        if self.0.state.lock()?.inactive {
+            return Ok(true);
+        }
+        let value = (self.0.value)();
+        if !self.satisfies_rules(&value)? {
+            return Ok(false);
+        }
+        if value.trim().is_empty() {
+            return Ok(true);
+        }
+
+        let checked = self.0.state.lock()?.value.as_ref() == Some(&value);
+        if !checked {
+            self.0.state.lock()?.value = Some(value.clone());
+            show_pending(&self.0.element, true)?;
+            self.check(value).await?;
+        }
+
+        let receiver = {
+            let mut state = self.0.state.lock()?;
+            if state.pending {
+                let (sender, receiver) = oneshot();
+                state.waiters.push(sender);
+                Some(receiver)
+            } else {
+                None
+            }
+        };
+        if let Some(receiver) = receiver {
+            receiver.recv().await?;
+        }
+
+        let message = self.0.state.lock()?.message.clone();
+        if message.is_some() {
+            show_message(&self.0.element, message.as_deref())?;
+        }
+        Ok(message.is_none())
+    }
+}
+
+/// Async validators of a layout, see [`DefaultFunctions::async_validators()`].
+#[derive(Clone, Default)]
+pub struct AsyncValidators(Arc<Mutex<Vec<AsyncValidator>>>);
+
+impl AsyncValidators {
+    pub fn push(&self, validator: AsyncValidator) -> Result<()> {
+        self.0.lock()?.push(validator);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.0.lock()?.is_empty())
+    }
+
+    pub fn is_pending(&self) -> Result<bool> {
+        for validator in self.0.lock()?.iter() {
+            if validator.is_pending()? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Waits for all validators; resolves to `false` if any of them failed.
+    pub async fn wait(&self) -> Result<bool> {
+        let validators = self.0.lock()?.clone();
+        let mut valid = true;
+        for validator in validators.iter() {
+            valid &= validator.wait().await?;
+        }
+        Ok(valid)
+    }
+}
+
+macro_rules! validatable {
+    ($($ctl:ty),+) => {
+        $(impl Validatable for $ctl {
+            fn validation_value(&self) -> String {
+                self.value()
+            }
+            fn validation_element(&self) -> Element {
+                self.element_wrapper.element.clone()
+            }
+        })+
+    };
+}
+
+validatable!(Input, Textarea, Mnemonic);
+
+impl Validatable for Checkbox {
+    fn validation_value(&self) -> String {
+        if self.value() {
+            "true".to_string()
+        } else {
+            String::new()
+        }
+    }
+    fn validation_element(&self) -> Element {
+        self.element()
+    }
+}
+
+impl<E: EnumTrait<E>> Validatable for Select<E> {
+    fn validation_value(&self) -> String {
+        self.value()
+    }
+    fn validation_element(&self) -> Element {
+        self.element_wrapper.element.clone()
+    }
+}
+
+impl<E: EnumTrait<E> + 'static + std::fmt::Display> Validatable for Radio<E> {
+    fn validation_value(&self) -> String {
+        self.value()
+    }
+    fn validation_element(&self) -> Element {
+        self.element()
+    }
+}
+
+impl<E: EnumTrait<E> + 'static + std::fmt::Display> Validatable for RadioBtns<E> {
+    fn validation_value(&self) -> String {
+        self.value()
+    }
+    fn validation_element(&self) -> Element {
+        self.element_wrapper.element.clone()
+    }
+}
+
+impl<E: EnumTrait<E> + std::fmt::Display> Validatable for Selector<E> {
+    fn validation_value(&self) -> String {
+        self.value()
+    }
+    fn validation_element(&self) -> Element {
+        self.element_wrapper.element.clone()
+    }
+}
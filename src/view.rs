@@ -1,21 +1,66 @@
 use std::{
     any::TypeId,
     collections::BTreeMap,
-    sync::{Arc, Mutex, RwLock},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock, Weak},
 };
 
+use crate::dialog;
+use crate::dirty::DirtyTracker;
+use crate::error::{error, Error};
 use crate::events::Emitter;
+use crate::icon::Icon;
+use crate::module;
+use crate::observer::ViewObserver;
+use crate::state::{PersistedState, ViewState};
+use crate::transition::{self, Direction, Transition};
 use crate::{app_menu::AppMenu, events, prelude::*};
 use crate::{bottom_menu, layout, result::Result};
 use downcast::{downcast_sync, AnySync};
 use workflow_log::log_trace;
+use workflow_wasm::callback::{Callback, CallbackMap};
+use workflow_wasm::prelude::callback;
+
+pub static CSS: &str = include_str!("view.css");
 
 //use web_sys::{ScrollBehavior, ScrollToOptions};
 //use crate::view::base_element::ExtendedElement;
+/// Selects a view stacked in a [`ContainerStack`] either by the id
+/// returned from [`ContainerStack::push_view`] or by the view type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewSelector {
+    Id(Id),
+    TypeId(TypeId),
+}
+
+impl From<Id> for ViewSelector {
+    fn from(id: Id) -> Self {
+        ViewSelector::Id(id)
+    }
+}
+
+impl From<TypeId> for ViewSelector {
+    fn from(typeid: TypeId) -> Self {
+        ViewSelector::TypeId(typeid)
+    }
+}
+
+impl ViewSelector {
+    fn matches(&self, id: &Id, view: &Arc<dyn View>) -> bool {
+        match self {
+            Self::Id(selected) => selected == id,
+            Self::TypeId(typeid) => *typeid == view.typeid(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ContainerStack {
     element: Element,
-    views: Arc<RwLock<Vec<Arc<dyn View>>>>,
+    views: Arc<RwLock<Vec<(Id, Arc<dyn View>)>>>,
+    max_depth: Arc<RwLock<Option<usize>>>,
+    observers: Arc<Mutex<BTreeMap<Id, ViewObserver>>>,
 }
 
 impl ContainerStack {
@@ -24,6 +69,8 @@ impl ContainerStack {
         Self {
             element,
             views: Arc::new(RwLock::new(Vec::new())),
+            max_depth: Arc::new(RwLock::new(None)),
+            observers: Arc::new(Mutex::new(BTreeMap::new())),
         }
     }
 
@@ -31,9 +78,206 @@ impl ContainerStack {
         self.element.clone()
     }
 
+    /// Limits the number of stacked views; when the limit is exceeded
+    /// the oldest (bottom) views are evicted.
+    pub async fn set_max_depth(self: &Arc<Self>, max_depth: Option<usize>) -> Result<()> {
+        *self.max_depth.write()? = max_depth;
+        self.enforce_max_depth().await
+    }
+
+    pub fn max_depth(&self) -> Result<Option<usize>> {
+        Ok(*self.max_depth.read()?)
+    }
+
     pub async fn append_view(self: &Arc<Self>, incoming: Arc<dyn View>) -> Result<()> {
-        (*self.views.write()?).push(incoming.clone());
+        self.push_view(incoming).await?;
+        Ok(())
+    }
+
+    /// Appends the view to the top of the stack.  Returns the id
+    /// selecting the view in [`ContainerStack::remove_view`] etc.
+    pub async fn push_view(self: &Arc<Self>, incoming: Arc<dyn View>) -> Result<Id> {
+        let id = Id::new();
+        (*self.views.write()?).push((id, incoming.clone()));
         self.element.append_child(&incoming.element())?;
+        self.show_view(id, &incoming).await?;
+        self.enforce_max_depth().await?;
+        Ok(id)
+    }
+
+    /// Removes and evicts the top view.
+    pub async fn pop_view(self: &Arc<Self>) -> Result<Option<Arc<dyn View>>> {
+        let view = self.views.write()?.pop();
+        if let Some((id, view)) = &view {
+            self.evict_view(*id, view).await?;
+            self.focus_top().await?;
+        }
+        Ok(view.map(|(_, view)| view))
+    }
+
+    /// Removes and evicts the topmost view matching the selector.
+    pub async fn remove_view<S>(self: &Arc<Self>, selector: S) -> Result<Option<Arc<dyn View>>>
+    where
+        S: Into<ViewSelector>,
+    {
+        let selector = selector.into();
+        let (view, was_top) = {
+            let mut views = self.views.write()?;
+            let len = views.len();
+            match views
+                .iter()
+                .rposition(|(id, view)| selector.matches(id, view))
+            {
+                Some(index) => (Some(views.remove(index)), index + 1 == len),
+                None => (None, false),
+            }
+        };
+        if let Some((id, view)) = &view {
+            self.evict_view(*id, view).await?;
+            if was_top {
+                self.focus_top().await?;
+            }
+        }
+        Ok(view.map(|(_, view)| view))
+    }
+
+    /// Replaces the topmost view matching the selector, keeping its
+    /// position in the stack.  Returns the evicted view.
+    pub async fn replace_view<S>(
+        self: &Arc<Self>,
+        selector: S,
+        incoming: Arc<dyn View>,
+    ) -> Result<Option<Arc<dyn View>>>
+    where
+        S: Into<ViewSelector>,
+    {
+        let selector = selector.into();
+        let previous = {
+            let mut views = self.views.write()?;
+            match views
+                .iter()
+                .rposition(|(id, view)| selector.matches(id, view))
+            {
+                Some(index) => {
+                    let id = views[index].0;
+                    Some((
+                        id,
+                        std::mem::replace(&mut views[index], (id, incoming.clone())).1,
+                    ))
+                }
+                None => None,
+            }
+        };
+
+        if let Some((id, previous)) = &previous {
+            self.element
+                .insert_before(&incoming.element(), Some(&previous.element()))?;
+            self.evict_view(*id, previous).await?;
+            self.show_view(*id, &incoming).await?;
+        }
+
+        Ok(previous.map(|(_, previous)| previous))
+    }
+
+    /// Removes and evicts all stacked views.
+    pub async fn clear(self: &Arc<Self>) -> Result<()> {
+        let views = std::mem::take(&mut *self.views.write()?);
+        for (id, view) in views.iter().rev() {
+            self.evict_view(*id, view).await?;
+        }
+        Ok(())
+    }
+
+    /// Stacked views, from the bottom to the top of the stack.
+    pub fn views(&self) -> Result<Vec<Arc<dyn View>>> {
+        Ok(self
+            .views
+            .read()?
+            .iter()
+            .map(|(_, view)| view.clone())
+            .collect())
+    }
+
+    /// Ids of the stacked views, from the bottom to the top of the stack.
+    pub fn ids(&self) -> Result<Vec<Id>> {
+        Ok(self.views.read()?.iter().map(|(id, _)| *id).collect())
+    }
+
+    pub fn get<S>(&self, selector: S) -> Result<Option<Arc<dyn View>>>
+    where
+        S: Into<ViewSelector>,
+    {
+        let selector = selector.into();
+        Ok(self
+            .views
+            .read()?
+            .iter()
+            .rev()
+            .find(|(id, view)| selector.matches(id, view))
+            .map(|(_, view)| view.clone()))
+    }
+
+    pub fn top(&self) -> Result<Option<Arc<dyn View>>> {
+        Ok(self.views.read()?.last().map(|(_, view)| view.clone()))
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.views.read()?.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.views.read()?.is_empty())
+    }
+
+    async fn enforce_max_depth(self: &Arc<Self>) -> Result<()> {
+        let evicted = match *self.max_depth.read()? {
+            Some(max_depth) => {
+                let mut views = self.views.write()?;
+                let excess = views.len().saturating_sub(max_depth);
+                views.drain(0..excess).collect::<Vec<_>>()
+            }
+            None => Vec::new(),
+        };
+
+        for (id, view) in evicted.iter() {
+            self.evict_view(*id, view).await?;
+        }
+        Ok(())
+    }
+
+    async fn show_view(&self, id: Id, view: &Arc<dyn View>) -> Result<()> {
+        view.subscribe()?;
+        self.observers
+            .lock()?
+            .insert(id, ViewObserver::try_new(view)?);
+        view.clone().on_show().await
+    }
+
+    async fn focus_top(&self) -> Result<()> {
+        if let Some(top) = self.top()? {
+            top.on_focus_return().await?;
+        }
+        Ok(())
+    }
+
+    async fn evict_view(self: &Arc<Self>, id: Id, view: &Arc<dyn View>) -> Result<()> {
+        self.observers.lock()?.remove(&id);
+        view.clone().on_hide().await?;
+        view.unsubscribe()?;
+        if let Some(module) = view.module() {
+            module.evict_stacked(self, view.clone()).await?;
+        }
+        Progress::abort(view);
+        let el = view.element();
+        let attached = el
+            .parent_node()
+            .map(|parent| parent.is_same_node(Some(&self.element)))
+            .unwrap_or(false);
+        if attached {
+            self.element.remove_child(&el)?;
+        }
+        view.clone().evict().await?;
+        View::drop(view.as_ref());
         Ok(())
     }
 }
@@ -44,11 +288,608 @@ impl From<ContainerStack> for Element {
     }
 }
 
+/// Appearance of a [`TabContainer`] tab.
+pub struct TabOptions {
+    pub title: String,
+    pub icon: Option<Icon>,
+    pub closable: bool,
+}
+
+impl TabOptions {
+    pub fn new<T: Into<String>>(title: T) -> Self {
+        TabOptions {
+            title: title.into(),
+            icon: None,
+            closable: true,
+        }
+    }
+
+    pub fn with_icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+}
+
+struct Tab {
+    id: Id,
+    element: Element,
+    title: Element,
+    pane: Element,
+    view: Option<Arc<dyn View>>,
+    container: Option<Arc<Container>>,
+}
+
+/// Holds several live views, one per tab, displaying only the active one.
+/// Switching tabs does not evict the views; a view is evicted only
+/// when its tab is closed.
+pub struct TabContainer {
+    element: Element,
+    strip: Element,
+    body: Element,
+    tabs: Arc<RwLock<Vec<Tab>>>,
+    active: Arc<Mutex<Option<Id>>>,
+    observers: Arc<Mutex<BTreeMap<Id, ViewObserver>>>,
+    change_cb: Arc<Mutex<Option<CallbackFn<Option<Id>>>>>,
+    callbacks: CallbackMap,
+}
+
+unsafe impl Sync for TabContainer {}
+unsafe impl Send for TabContainer {}
+
+impl TabContainer {
+    pub fn try_new(element: Element) -> Result<Arc<Self>> {
+        element.set_attribute("data-container-type", "tabs")?;
+        let strip = create_el("div.workspace-tab-strip", vec![], None)?;
+        let body = create_el("div.workspace-tab-body", vec![], None)?;
+        element.append_child(&strip)?;
+        element.append_child(&body)?;
+
+        let tabs = Arc::new(TabContainer {
+            element,
+            strip,
+            body,
+            tabs: Arc::new(RwLock::new(Vec::new())),
+            active: Arc::new(Mutex::new(None)),
+            observers: Arc::new(Mutex::new(BTreeMap::new())),
+            change_cb: Arc::new(Mutex::new(None)),
+            callbacks: CallbackMap::new(),
+        });
+        tabs.init()?;
+        Ok(tabs)
+    }
+
+    fn init(self: &Arc<Self>) -> Result<()> {
+        let this: Weak<Self> = Arc::downgrade(self);
+        let callback = callback!(move |event: web_sys::MouseEvent| -> Result<()> {
+            let target = match event.target().and_then(|t| t.dyn_into::<Element>().ok()) {
+                Some(target) => target,
+                None => return Ok(()),
+            };
+            let id = match target.closest("[data-tab]")? {
+                Some(tab) => tab.get_attribute("data-tab").unwrap_or_default(),
+                None => return Ok(()),
+            };
+            let id = id
+                .parse::<Id>()
+                .map_err(|err| error!("TabContainer: invalid tab id `{}`: {}", id, err))?;
+            let close = target.closest(".workspace-tab-close")?.is_some();
+            if let Some(this) = this.upgrade() {
+                workflow_core::task::wasm::dispatch(async move {
+                    let result = if close {
+                        this.close_tab(id).await
+                    } else {
+                        this.focus_tab(id).await
+                    };
+                    if let Err(err) = result {
+                        log_error!("TabContainer: tab action failure: {}", err);
+                    }
+                });
+            }
+            Ok(())
+        });
+        self.strip
+            .add_event_listener_with_callback("click", callback.as_ref())?;
+        self.callbacks.retain(callback)?;
+        Ok(())
+    }
+
+    pub fn element(&self) -> Element {
+        self.element.clone()
+    }
+
+    /// Opens the view in a new tab and makes it active.
+    pub async fn open_tab(
+        self: &Arc<Self>,
+        view: Arc<dyn View>,
+        options: TabOptions,
+    ) -> Result<Id> {
+        let id = Id::new();
+        let pane = self.create_tab(id, &options, Some(view.clone()), None)?;
+        pane.append_child(&view.element())?;
+        view.subscribe()?;
+        self.observers
+            .lock()?
+            .insert(id, ViewObserver::try_new(&view)?);
+        view.clone().on_show().await?;
+        self.focus_tab(id).await?;
+        Ok(id)
+    }
+
+    /// Creates a tab hosting a [`Container`], allowing regular view
+    /// navigation within the tab (used to mount the workspace main
+    /// container in a tab).
+    pub fn open_container_tab(
+        self: &Arc<Self>,
+        options: TabOptions,
+        app_menu: Option<Arc<AppMenu>>,
+    ) -> Result<(Id, Arc<Container>)> {
+        let id = Id::new();
+        let pane = create_el(
+            "div.workspace-tab-pane",
+            vec![("data-tab", &id.to_string())],
+            None,
+        )?;
+        let container = Arc::new(Container::new(pane, app_menu));
+        self.create_tab(id, &options, None, Some(container.clone()))?;
+        if self.active()?.is_none() {
+            self.activate(id)?;
+        }
+        Ok((id, container))
+    }
+
+    fn create_tab(
+        &self,
+        id: Id,
+        options: &TabOptions,
+        view: Option<Arc<dyn View>>,
+        container: Option<Arc<Container>>,
+    ) -> Result<Element> {
+        let element = create_el(
+            "div.workspace-tab",
+            vec![("data-tab", &id.to_string())],
+            None,
+        )?;
+        if let Some(icon) = &options.icon {
+            element.append_child(&icon.element()?)?;
+        }
+        let title = create_el("span.title", vec![], None)?;
+        title.set_text_content(Some(&options.title));
+        element.append_child(&title)?;
+        if options.closable {
+            let close = Icon::css("close").element()?;
+            close.class_list().add_1("workspace-tab-close")?;
+            element.append_child(&close)?;
+        }
+        self.strip.append_child(&element)?;
+
+        let pane = match &container {
+            Some(container) => container.element(),
+            None => create_el(
+                "div.workspace-tab-pane",
+                vec![("data-tab", &id.to_string())],
+                None,
+            )?,
+        };
+        pane.set_attribute("hidden", "true")?;
+        self.body.append_child(&pane)?;
+
+        self.tabs.write()?.push(Tab {
+            id,
+            element,
+            title,
+            pane: pane.clone(),
+            view,
+            container,
+        });
+        Ok(pane)
+    }
+
+    /// Activates the first tab matching the selector.
+    /// Returns `false` if no such tab exists.
+    pub async fn focus_tab<S>(self: &Arc<Self>, selector: S) -> Result<bool>
+    where
+        S: Into<ViewSelector>,
+    {
+        let (id, view) = match self.find(selector.into())? {
+            Some(tab) => tab,
+            None => return Ok(false),
+        };
+        if self.active()? == Some(id) {
+            return Ok(true);
+        }
+        self.activate(id)?;
+        if let Some(view) = view {
+            view.on_focus_return().await?;
+        }
+        Ok(true)
+    }
+
+    /// Closes the first tab matching the selector, evicting its view
+    /// (or the views of its container, see [`Container::close`]).
+    /// Returns `false` if no such tab exists or the view refused eviction.
+    pub async fn close_tab<S>(self: &Arc<Self>, selector: S) -> Result<bool>
+    where
+        S: Into<ViewSelector>,
+    {
+        let (id, view) = match self.find(selector.into())? {
+            Some(tab) => tab,
+            None => return Ok(false),
+        };
+        if let Some(view) = &view {
+            if !query_eviction(view).await? {
+                return Ok(false);
+            }
+        }
+        let container = self
+            .tabs
+            .read()?
+            .iter()
+            .find(|tab| tab.id == id)
+            .and_then(|tab| tab.container.clone());
+        if let Some(container) = container {
+            if !container.close().await? {
+                return Ok(false);
+            }
+        }
+
+        let (tab, next) = {
+            let mut tabs = self.tabs.write()?;
+            let index = match tabs.iter().position(|tab| tab.id == id) {
+                Some(index) => index,
+                None => return Ok(false),
+            };
+            let tab = tabs.remove(index);
+            let next = tabs.get(index).or_else(|| tabs.last()).map(|tab| tab.id);
+            (tab, next)
+        };
+
+        if let Some(view) = &tab.view {
+            self.observers.lock()?.remove(&id);
+            view.clone().on_hide().await?;
+            view.unsubscribe()?;
+        }
+        self.strip.remove_child(&tab.element)?;
+        self.body.remove_child(&tab.pane)?;
+        if let Some(view) = &tab.view {
+            view.clone().evict().await?;
+            View::drop(view.as_ref());
+        }
+
+        if self.active()? == Some(id) {
+            *self.active.lock()? = None;
+            match next {
+                Some(next) => {
+                    self.focus_tab(next).await?;
+                }
+                None => self.notify_change()?,
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn set_title<T: Into<String>>(&self, id: Id, title: T) -> Result<()> {
+        if let Some(tab) = self.tabs.read()?.iter().find(|tab| tab.id == id) {
+            tab.title.set_text_content(Some(&title.into()));
+        }
+        Ok(())
+    }
+
+    pub fn active(&self) -> Result<Option<Id>> {
+        Ok(*self.active.lock()?)
+    }
+
+    pub fn active_view(&self) -> Result<Option<Arc<dyn View>>> {
+        let active = self.active()?;
+        Ok(self
+            .tabs
+            .read()?
+            .iter()
+            .find(|tab| Some(tab.id) == active)
+            .and_then(|tab| tab.view.clone()))
+    }
+
+    pub fn get<S>(&self, selector: S) -> Result<Option<Arc<dyn View>>>
+    where
+        S: Into<ViewSelector>,
+    {
+        Ok(self.find(selector.into())?.and_then(|(_, view)| view))
+    }
+
+    /// Ids of the open tabs in the tab strip order.
+    pub fn ids(&self) -> Result<Vec<Id>> {
+        Ok(self.tabs.read()?.iter().map(|tab| tab.id).collect())
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.tabs.read()?.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.tabs.read()?.is_empty())
+    }
+
+    /// Invoked with the id of the active tab whenever it changes.
+    pub fn on_change(&self, callback: CallbackFn<Option<Id>>) -> Result<()> {
+        *self.change_cb.lock()? = Some(callback);
+        Ok(())
+    }
+
+    fn find(&self, selector: ViewSelector) -> Result<Option<(Id, Option<Arc<dyn View>>)>> {
+        Ok(self
+            .tabs
+            .read()?
+            .iter()
+            .find(|tab| match (&selector, &tab.view) {
+                (ViewSelector::Id(id), _) => *id == tab.id,
+                (_, Some(view)) => selector.matches(&tab.id, view),
+                (_, None) => false,
+            })
+            .map(|tab| (tab.id, tab.view.clone())))
+    }
+
+    fn activate(&self, id: Id) -> Result<()> {
+        for tab in self.tabs.read()?.iter() {
+            if tab.id == id {
+                tab.element.class_list().add_1("active")?;
+                tab.pane.remove_attribute("hidden")?;
+            } else {
+                tab.element.class_list().remove_1("active")?;
+                tab.pane.set_attribute("hidden", "true")?;
+            }
+        }
+        *self.active.lock()? = Some(id);
+        self.notify_change()
+    }
+
+    fn notify_change(&self) -> Result<()> {
+        let active = self.active()?;
+        if let Some(cb) = self.change_cb.lock()?.as_mut() {
+            cb(active)?;
+        }
+        Ok(())
+    }
+}
+
+pub type ViewFactoryFuture = Pin<Box<dyn Future<Output = Result<Arc<dyn View>>>>>;
+pub type ViewFactory = Arc<dyn Fn() -> ViewFactoryFuture>;
+
+/// Previously shown view recorded in the [`Container`] navigation history.
+/// Views are either kept alive (parked without eviction) or rebuilt
+/// from the factory they were loaded with.
+#[derive(Clone)]
+pub enum HistoryEntry {
+    View(Arc<dyn View>),
+    Factory(ViewFactory),
+}
+
+impl HistoryEntry {
+    pub fn view(&self) -> Option<Arc<dyn View>> {
+        match self {
+            Self::View(view) => Some(view.clone()),
+            Self::Factory(_) => None,
+        }
+    }
+
+    async fn resolve(&self) -> Result<(Arc<dyn View>, Option<ViewFactory>)> {
+        match self {
+            Self::View(view) => Ok((view.clone(), None)),
+            Self::Factory(factory) => Ok((factory().await?, Some(factory.clone()))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryState {
+    pub can_go_back: bool,
+    pub can_go_forward: bool,
+}
+
+#[derive(Default)]
+struct NavigationHistory {
+    back: Vec<HistoryEntry>,
+    forward: Vec<HistoryEntry>,
+    keep_alive: bool,
+    back_menu: bool,
+}
+
+impl NavigationHistory {
+    fn state(&self) -> HistoryState {
+        HistoryState {
+            can_go_back: !self.back.is_empty(),
+            can_go_forward: !self.forward.is_empty(),
+        }
+    }
+
+    fn contains(&self, view: &Arc<dyn View>) -> bool {
+        self.back
+            .iter()
+            .chain(self.forward.iter())
+            .filter_map(|entry| entry.view())
+            .any(|entry| same_view(&entry, view))
+    }
+
+    /// Records the entry in the history of the supplied direction.  Returns
+    /// the views kept alive by the oldest entries exceeding [`MAX_HISTORY_SIZE`].
+    fn push(&mut self, direction: Direction, entry: HistoryEntry) -> Vec<Arc<dyn View>> {
+        let entries = match direction {
+            Direction::Back => &mut self.back,
+            Direction::Forward => &mut self.forward,
+        };
+        entries.push(entry);
+        let excess = entries.len().saturating_sub(MAX_HISTORY_SIZE);
+        entries
+            .drain(0..excess)
+            .filter_map(|entry| entry.view())
+            .collect()
+    }
+}
+
+/// Number of entries kept in each direction of the [`Container`] navigation history.
+pub const MAX_HISTORY_SIZE: usize = 32;
+
+/// Number of views kept by the default [`CachePolicy`].
+pub const DEFAULT_CACHE_SIZE: usize = 8;
+
+/// Eviction policy of the [`Container`] keep-alive [`ViewCache`].
+/// Defaults to [`CachePolicy::Lru`] of [`DEFAULT_CACHE_SIZE`] views, so
+/// views opting in via [`View::keep_alive`] are parked without further setup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Swapped out views are evicted immediately.
+    Disabled,
+    /// Keeps up to the given number of views, evicting the least recently used.
+    Lru(usize),
+    /// Keeps all parked views until the cache is cleared.
+    Unbounded,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy::Lru(DEFAULT_CACHE_SIZE)
+    }
+}
+
+/// Views parked by a [`Container`] after being swapped out.  Only views
+/// opting in via [`View::keep_alive`] are cached; entries are keyed by
+/// the view `typeid` and the optional [`View::cache_key`].
+#[derive(Default)]
+pub struct ViewCache {
+    policy: CachePolicy,
+    // least recently used first
+    views: Vec<(TypeId, Option<String>, Arc<dyn View>)>,
+}
+
+impl ViewCache {
+    pub fn new(policy: CachePolicy) -> Self {
+        ViewCache {
+            policy,
+            views: Vec::new(),
+        }
+    }
+
+    pub fn policy(&self) -> CachePolicy {
+        self.policy
+    }
+
+    /// Changes the policy, returning the views that no longer fit in the cache.
+    pub fn set_policy(&mut self, policy: CachePolicy) -> Vec<Arc<dyn View>> {
+        self.policy = policy;
+        self.overflow()
+    }
+
+    /// Returns `true` if the view can be parked in the cache.
+    pub fn accepts(&self, view: &Arc<dyn View>) -> bool {
+        self.policy != CachePolicy::Disabled && view.keep_alive()
+    }
+
+    pub fn get(&self, typeid: TypeId, key: Option<&str>) -> Option<Arc<dyn View>> {
+        self.views
+            .iter()
+            .find(|(t, k, _)| *t == typeid && k.as_deref() == key)
+            .map(|(_, _, view)| view.clone())
+    }
+
+    pub fn contains(&self, view: &Arc<dyn View>) -> bool {
+        self.views.iter().any(|(_, _, v)| same_view(v, view))
+    }
+
+    /// Parks the view as the most recently used entry.  Returns the views
+    /// evicted from the cache: a previous view with the same key and the
+    /// least recently used views exceeding the cache size.
+    pub fn park(&mut self, view: Arc<dyn View>) -> Vec<Arc<dyn View>> {
+        let typeid = view.typeid();
+        let key = view.cache_key();
+        let mut evicted = Vec::new();
+        if let Some(index) = self
+            .views
+            .iter()
+            .position(|(t, k, _)| *t == typeid && *k == key)
+        {
+            let (_, _, previous) = self.views.remove(index);
+            if !same_view(&previous, &view) {
+                evicted.push(previous);
+            }
+        }
+        self.views.push((typeid, key, view));
+        evicted.extend(self.overflow());
+        evicted
+    }
+
+    /// Removes the view from the cache.  Returns `false` if it was not cached.
+    pub fn take(&mut self, view: &Arc<dyn View>) -> bool {
+        let len = self.views.len();
+        self.views.retain(|(_, _, v)| !same_view(v, view));
+        self.views.len() != len
+    }
+
+    pub fn clear(&mut self) -> Vec<Arc<dyn View>> {
+        self.views.drain(..).map(|(_, _, view)| view).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+
+    fn overflow(&mut self) -> Vec<Arc<dyn View>> {
+        let excess = match self.policy {
+            CachePolicy::Disabled => self.views.len(),
+            CachePolicy::Lru(size) => self.views.len().saturating_sub(size),
+            CachePolicy::Unbounded => 0,
+        };
+        self.views
+            .drain(0..excess)
+            .map(|(_, _, view)| view)
+            .collect()
+    }
+}
+
+/// Maps [`Error::EvictionCancelled`] to `Ok(None)`.
+fn unless_cancelled<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Err(Error::EvictionCancelled) => Ok(None),
+        result => result.map(Some),
+    }
+}
+
+/// Returns `true` if the view can be evicted, asking the user
+/// to confirm discarding unsaved changes if necessary.
+pub(crate) async fn query_eviction(view: &Arc<dyn View>) -> Result<bool> {
+    match view.clone().eviction().await? {
+        Eviction::Allow => Ok(true),
+        Eviction::Disallow => Ok(false),
+        Eviction::Confirm => dialog::confirm_unsaved_changes().await,
+    }
+}
+
+pub(crate) fn same_view(a: &Arc<dyn View>, b: &Arc<dyn View>) -> bool {
+    Arc::as_ptr(a) as *const () == Arc::as_ptr(b) as *const ()
+}
+
 #[derive(Clone)]
 pub struct Container {
     element: Element,
     view: Arc<RwLock<Option<Arc<dyn View>>>>,
     app_menu: Option<Arc<AppMenu>>,
+    transition: Arc<RwLock<(Transition, u32)>>,
+    /// Held while a swap (and its transition) is in progress
+    swap_lock: Arc<async_std::sync::Mutex<()>>,
+    factory: Arc<Mutex<Option<ViewFactory>>>,
+    history: Arc<Mutex<NavigationHistory>>,
+    history_cb: Arc<Mutex<Option<CallbackFn<HistoryState>>>>,
+    back_item: Arc<Mutex<Option<bottom_menu::BottomMenuItem>>>,
+    observer: Arc<Mutex<Option<ViewObserver>>>,
+    cache: Arc<Mutex<ViewCache>>,
+    state_key: Arc<Mutex<Option<String>>>,
+    state_listener: Arc<Mutex<Option<Callback<dyn FnMut(web_sys::Event) -> Result<()>>>>>,
 }
 
 unsafe impl Sync for Container {}
@@ -60,6 +901,19 @@ impl Container {
             element,
             view: Arc::new(RwLock::new(None)),
             app_menu,
+            transition: Arc::new(RwLock::new((
+                Transition::None,
+                transition::DEFAULT_DURATION,
+            ))),
+            swap_lock: Arc::new(async_std::sync::Mutex::new(())),
+            factory: Arc::new(Mutex::new(None)),
+            history: Arc::new(Mutex::new(NavigationHistory::default())),
+            history_cb: Arc::new(Mutex::new(None)),
+            back_item: Arc::new(Mutex::new(None)),
+            observer: Arc::new(Mutex::new(None)),
+            cache: Arc::new(Mutex::new(ViewCache::default())),
+            state_key: Arc::new(Mutex::new(None)),
+            state_listener: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -67,15 +921,107 @@ impl Container {
         self.element.clone()
     }
 
+    /// Sets the default transition played by this container when swapping views.
+    pub fn set_transition(&self, transition: Transition, duration: u32) -> Result<()> {
+        if transition == Transition::None {
+            self.element.remove_attribute("data-container-transition")?;
+        } else {
+            self.element
+                .set_attribute("data-container-transition", transition.name())?;
+        }
+        *self.transition.write()? = (transition, duration);
+        Ok(())
+    }
+
+    pub fn transition(&self) -> Result<(Transition, u32)> {
+        Ok(*self.transition.read()?)
+    }
+
+    /// Loads the view using the container default transition.  Returns the
+    /// previous view, or `None` without loading the view if the current
+    /// view refused eviction (see [`Container::try_load_view`]).
     pub async fn load_view(
         self: &Arc<Self>,
         incoming: Arc<dyn View>,
     ) -> Result<Option<Arc<dyn View>>> {
-        let from = self.swap_from().await?;
+        let (transition, _) = self.transition()?;
+        self.load_view_with_transition(incoming, transition, Direction::Forward)
+            .await
+    }
+
+    /// Loads the view using the container default transition.
+    /// Returns `false` if the current view refused eviction.
+    pub async fn try_load_view(self: &Arc<Self>, incoming: Arc<dyn View>) -> Result<bool> {
+        let (transition, _) = self.transition()?;
+        let loaded = self
+            .load(incoming, transition, Direction::Forward)
+            .await?
+            .is_some();
+        Ok(loaded)
+    }
+
+    /// Loads the view playing the supplied transition instead of the container default.
+    pub async fn load_view_with_transition(
+        self: &Arc<Self>,
+        incoming: Arc<dyn View>,
+        transition: Transition,
+        direction: Direction,
+    ) -> Result<Option<Arc<dyn View>>> {
+        let from = self.load(incoming, transition, direction).await?;
+        Ok(from.flatten())
+    }
+
+    /// Loads the view produced by the factory.  When the container does not
+    /// keep views alive, the factory is recorded in the navigation history
+    /// and used to rebuild the view on [`Container::back`]/[`Container::forward`].
+    /// The factory is not invoked if the current view refused eviction.
+    pub async fn load_view_with_factory(
+        self: &Arc<Self>,
+        factory: ViewFactory,
+    ) -> Result<Option<Arc<dyn View>>> {
+        let from = match unless_cancelled(self.swap_from().await)? {
+            Some(from) => from,
+            None => return Ok(None),
+        };
+        let discarded = self.push_history()?;
+        let incoming = factory().await?;
+        *self.factory.lock()? = Some(factory);
         self.swap_to(incoming).await?;
+        self.discard(discarded).await?;
         Ok(from)
     }
 
+    /// Shared load path recording the current view in the navigation
+    /// history.  Returns `None` if the current view refused eviction,
+    /// otherwise the previous view.
+    pub(crate) async fn load(
+        self: &Arc<Self>,
+        incoming: Arc<dyn View>,
+        transition: Transition,
+        direction: Direction,
+    ) -> Result<Option<Option<Arc<dyn View>>>> {
+        let from = match unless_cancelled(self.swap_from().await)? {
+            Some(from) => from,
+            None => return Ok(None),
+        };
+        let discarded = self.push_history()?;
+        *self.factory.lock()? = None;
+        self.swap_to_with_transition(incoming, transition, direction)
+            .await?;
+        self.discard(discarded).await?;
+        Ok(Some(from))
+    }
+
+    /// Loads the view playing the reverse of the container default transition.
+    pub async fn load_view_back(
+        self: &Arc<Self>,
+        incoming: Arc<dyn View>,
+    ) -> Result<Option<Arc<dyn View>>> {
+        let (transition, _) = self.transition()?;
+        self.load_view_with_transition(incoming, transition, Direction::Back)
+            .await
+    }
+
     pub async fn load_html(
         self: &Arc<Self>,
         // module : Arc<dyn ModuleInterface>,
@@ -83,15 +1029,379 @@ impl Container {
     ) -> Result<Option<Arc<dyn View>>> {
         // let view = view::Html::try_new(module.clone(), html)?;
         let view = view::Html::try_new(None, html)?;
-        let from = self.swap_from().await?;
-        self.swap_to(view).await?;
-        Ok(from)
+        self.load_view(view).await
+    }
+
+    /// Keep previous views alive in the navigation history instead of
+    /// evicting them and rebuilding them from their factories.
+    pub fn set_keep_alive(&self, keep_alive: bool) -> Result<()> {
+        self.history.lock()?.keep_alive = keep_alive;
+        Ok(())
+    }
+
+    /// Sets the eviction policy of the keep-alive view cache,
+    /// evicting cached views exceeding the new limit.
+    pub async fn set_cache_policy(self: &Arc<Self>, policy: CachePolicy) -> Result<()> {
+        let evicted = self.cache.lock()?.set_policy(policy);
+        self.evict_cached(evicted).await
+    }
+
+    pub fn cache_policy(&self) -> Result<CachePolicy> {
+        Ok(self.cache.lock()?.policy())
+    }
+
+    /// Returns the cached view of the supplied type and key, if any.
+    pub fn cached_view(&self, typeid: TypeId, key: Option<&str>) -> Result<Option<Arc<dyn View>>> {
+        Ok(self.cache.lock()?.get(typeid, key))
+    }
+
+    /// Loads the cached view of the supplied type and key.  Returns `false`
+    /// if no such view is cached or the current view refused eviction.
+    pub async fn restore_view(self: &Arc<Self>, typeid: TypeId, key: Option<&str>) -> Result<bool> {
+        match self.cached_view(typeid, key)? {
+            Some(view) => self.try_load_view(view).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Evicts the displayed view along with the views kept alive in the
+    /// navigation history and the keep-alive cache, leaving the container
+    /// empty.  Returns `false` if the displayed view refused eviction.
+    pub async fn close(self: &Arc<Self>) -> Result<bool> {
+        if let Some(view) = self.view() {
+            if !query_eviction(&view).await? {
+                return Ok(false);
+            }
+        }
+        let swap = self.swap_lock.lock().await;
+        let current = self.view.write()?.take();
+        *self.factory.lock()? = None;
+        if let Some(view) = &current {
+            self.observer.lock()?.take();
+            view.clone().on_hide().await?;
+            view.unsubscribe()?;
+            Progress::abort(view);
+            self.element.remove_child(&view.element())?;
+        }
+        drop(swap);
+
+        if let Some(view) = current {
+            self.evict_view(view).await?;
+        }
+        self.clear_history().await?;
+        self.clear_cache().await?;
+        Ok(true)
+    }
+
+    /// Evicts all views parked in the keep-alive cache.
+    pub async fn clear_cache(self: &Arc<Self>) -> Result<()> {
+        let evicted = self.cache.lock()?.clear();
+        self.evict_cached(evicted).await
+    }
+
+    async fn evict_cached(self: &Arc<Self>, views: Vec<Arc<dyn View>>) -> Result<()> {
+        for view in views {
+            if !self.history.lock()?.contains(&view) {
+                self.evict_view(view).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Evicts a view that is neither displayed, kept alive in the
+    /// history nor parked in the cache.
+    async fn evict_view(self: &Arc<Self>, view: Arc<dyn View>) -> Result<()> {
+        if let Some(module) = view.module() {
+            module.evict(self, view.clone()).await?;
+        }
+        view.clone().evict().await?;
+        View::drop(view.as_ref());
+        Ok(())
+    }
+
+    /// Persists the state of the displayed view (see [`View::snapshot`])
+    /// to `local_storage()` under the supplied key before the view is
+    /// swapped out and when the page is hidden.  `None` disables persistence.
+    pub fn set_state_key(self: &Arc<Self>, key: Option<&str>) -> Result<()> {
+        *self.state_key.lock()? = key.map(String::from);
+
+        let mut state_listener = self.state_listener.lock()?;
+        if key.is_some() && state_listener.is_none() {
+            let this: Weak<Self> = Arc::downgrade(self);
+            let callback = callback!(move |event: web_sys::Event| -> Result<()> {
+                if event.type_() == "pagehide" || document().hidden() {
+                    if let Some(this) = this.upgrade() {
+                        this.save_state()?;
+                    }
+                }
+                Ok(())
+            });
+            window().add_event_listener_with_callback("pagehide", callback.as_ref())?;
+            document().add_event_listener_with_callback("visibilitychange", callback.as_ref())?;
+            *state_listener = Some(callback);
+        }
+        Ok(())
+    }
+
+    /// Stores the snapshot of the displayed view.  Returns `false`
+    /// if persistence is disabled or the view has no state to persist.
+    pub fn save_state(&self) -> Result<bool> {
+        let key = match self.state_key.lock()?.clone() {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+        let view = match self.view() {
+            Some(view) => view,
+            None => return Ok(false),
+        };
+        let module = view
+            .module()
+            .and_then(|module| module::get_module_name(&module));
+        match (module, view.snapshot()?) {
+            (Some(module), Some(mut state)) => {
+                state.scroll_top = self.element.scroll_top();
+                PersistedState { module, state }.store(&key)?;
+                Ok(true)
+            }
+            _ => {
+                PersistedState::clear(&key)?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Rebuilds the view persisted under the state key by its module
+    /// (see [`ModuleInterface::restore`]) and loads it.  Returns `false`
+    /// if there is no persisted state or the view could not be restored.
+    pub async fn restore_state(self: &Arc<Self>) -> Result<bool> {
+        let key = match self.state_key.lock()?.clone() {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+        let persisted = match PersistedState::load(&key) {
+            Ok(Some(persisted)) => persisted,
+            Ok(None) => return Ok(false),
+            Err(err) => {
+                log_warning!("Container: discarding invalid view state: {}", err);
+                PersistedState::clear(&key)?;
+                return Ok(false);
+            }
+        };
+        let module = match persisted.module() {
+            Ok(module) => module,
+            Err(err) => {
+                log_warning!("{}", err);
+                return Ok(false);
+            }
+        };
+        let view = match module.restore(&persisted.state).await? {
+            Some(view) => view,
+            None => return Ok(false),
+        };
+
+        if !self.try_load_view(view.clone()).await? {
+            return Ok(false);
+        }
+        view.rehydrate(&persisted.state).await?;
+        self.element.set_scroll_top(persisted.state.scroll_top);
+        Ok(true)
+    }
+
+    /// Show a back item in the bottom menu while the history is not empty.
+    pub fn set_back_menu(&self, back_menu: bool) -> Result<()> {
+        self.history.lock()?.back_menu = back_menu;
+        Ok(())
+    }
+
+    pub fn can_go_back(&self) -> Result<bool> {
+        Ok(self.history.lock()?.state().can_go_back)
+    }
+
+    pub fn can_go_forward(&self) -> Result<bool> {
+        Ok(self.history.lock()?.state().can_go_forward)
+    }
+
+    pub fn history_state(&self) -> Result<HistoryState> {
+        Ok(self.history.lock()?.state())
+    }
+
+    pub fn on_history_change(&self, callback: CallbackFn<HistoryState>) -> Result<()> {
+        *self.history_cb.lock()? = Some(callback);
+        Ok(())
+    }
+
+    /// Navigates to the previous view in the history.
+    /// Returns `false` if the history is empty or the navigation was cancelled.
+    pub async fn back(self: &Arc<Self>) -> Result<bool> {
+        let entry = self.history.lock()?.back.pop();
+        match entry {
+            Some(entry) => self.navigate_history(entry, Direction::Back).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Navigates to the next view in the history.
+    /// Returns `false` if there is no view to go forward to
+    /// or the navigation was cancelled.
+    pub async fn forward(self: &Arc<Self>) -> Result<bool> {
+        let entry = self.history.lock()?.forward.pop();
+        match entry {
+            Some(entry) => self.navigate_history(entry, Direction::Forward).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Clears the navigation history evicting views kept alive in it.
+    pub async fn clear_history(self: &Arc<Self>) -> Result<()> {
+        let discarded = {
+            let mut history = self.history.lock()?;
+            let mut entries = history.back.drain(..).collect::<Vec<_>>();
+            entries.extend(history.forward.drain(..));
+            entries
+                .into_iter()
+                .filter_map(|entry| entry.view())
+                .collect::<Vec<_>>()
+        };
+        self.discard(discarded).await?;
+        self.notify_history_change()?;
+        Ok(())
+    }
+
+    async fn navigate_history(
+        self: &Arc<Self>,
+        entry: HistoryEntry,
+        direction: Direction,
+    ) -> Result<bool> {
+        match self.swap_from().await {
+            Err(Error::EvictionCancelled) => {
+                let mut history = self.history.lock()?;
+                match direction {
+                    Direction::Back => history.back.push(entry),
+                    Direction::Forward => history.forward.push(entry),
+                }
+                return Ok(false);
+            }
+            result => result?,
+        };
+        let discarded = match self.history_entry()? {
+            Some(current) => {
+                let opposite = match direction {
+                    Direction::Back => Direction::Forward,
+                    Direction::Forward => Direction::Back,
+                };
+                self.history.lock()?.push(opposite, current)
+            }
+            None => Vec::new(),
+        };
+        let restored = matches!(entry, HistoryEntry::View(_));
+        let (incoming, factory) = entry.resolve().await?;
+        *self.factory.lock()? = factory;
+        let (transition, _) = self.transition()?;
+        self.swap_to_with_transition(incoming.clone(), transition, direction)
+            .await?;
+        self.discard(discarded).await?;
+        if restored {
+            incoming.on_focus_return().await?;
+        }
+        Ok(true)
+    }
+
+    /// History entry representing the current view.  Views loaded without
+    /// a factory can not be rebuilt; they are recorded only if kept alive by
+    /// the container or opting in via [`View::keep_alive`], otherwise they are
+    /// evicted (or parked in the cache) once swapped out.
+    fn history_entry(&self) -> Result<Option<HistoryEntry>> {
+        let keep_alive = self.history.lock()?.keep_alive;
+        let entry = match (self.factory.lock()?.clone(), self.view()) {
+            (Some(factory), _) if !keep_alive => Some(HistoryEntry::Factory(factory)),
+            (_, Some(view)) if keep_alive || view.keep_alive() => Some(HistoryEntry::View(view)),
+            _ => None,
+        };
+        Ok(entry)
+    }
+
+    /// Records the current view in the history, discarding the forward
+    /// entries and the oldest entries exceeding [`MAX_HISTORY_SIZE`].
+    /// Returns views kept alive by the discarded entries.
+    fn push_history(&self) -> Result<Vec<Arc<dyn View>>> {
+        let current = self.history_entry()?;
+        let mut history = self.history.lock()?;
+        let mut discarded = history
+            .forward
+            .drain(..)
+            .filter_map(|entry| entry.view())
+            .collect::<Vec<_>>();
+        if let Some(current) = current {
+            discarded.extend(history.push(Direction::Back, current));
+        }
+        Ok(discarded)
+    }
+
+    /// Evicts views dropped from the navigation history unless they are
+    /// displayed, still recorded in the history or parked in the cache.
+    async fn discard(self: &Arc<Self>, views: Vec<Arc<dyn View>>) -> Result<()> {
+        for view in views {
+            let displayed = self
+                .view()
+                .map_or(false, |current| same_view(&current, &view));
+            if !displayed
+                && !self.history.lock()?.contains(&view)
+                && !self.cache.lock()?.contains(&view)
+            {
+                self.evict_view(view).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn notify_history_change(&self) -> Result<()> {
+        let state = self.history_state()?;
+        if let Some(cb) = self.history_cb.lock()?.as_mut() {
+            cb(state)?;
+        }
+        Ok(())
+    }
+
+    fn back_menu_item(self: &Arc<Self>) -> Result<Option<bottom_menu::BottomMenuItem>> {
+        {
+            let history = self.history.lock()?;
+            if !history.back_menu || history.back.is_empty() {
+                return Ok(None);
+            }
+        }
+
+        let mut back_item = self.back_item.lock()?;
+        if back_item.is_none() {
+            let this: Weak<Self> = Arc::downgrade(self);
+            let item = bottom_menu::new_item(
+                i18n("Back"),
+                Icon::css("info-row-arrow-right"),
+                move |_event| -> Result<()> {
+                    if let Some(this) = this.upgrade() {
+                        workflow_core::task::wasm::dispatch(async move {
+                            if let Err(err) = this.back().await {
+                                log_error!("Container::back() failure: {}", err);
+                            }
+                        });
+                    }
+                    Ok(())
+                },
+            )?;
+            // point the arrow back
+            item.icon_el
+                .set_attribute("transform", "rotate(180 0 32)")?;
+            *back_item = Some(item);
+        }
+
+        Ok(back_item.clone())
     }
 
     /// Initiates view swap.  Must be called before creating any objects for
     /// the next view.  This function checks if the current view can be
-    /// safely evicted allowing the owning module to query user for confirmation
-    /// if necessary.
+    /// safely evicted (see [`View::eviction`]), querying the user for
+    /// confirmation if necessary.  Returns [`Error::EvictionCancelled`]
+    /// if the swap must not proceed.  The view itself is evicted by
+    /// [`Container::swap_to`] once its exit transition completes.
     pub async fn swap_from(self: &Arc<Self>) -> Result<Option<Arc<dyn View>>> {
         let previous = self.view.read()?.clone();
         match &previous {
@@ -100,11 +1410,14 @@ impl Container {
                 Ok(None)
             }
             Some(previous) => {
-                // let module = previous.module();
-                if let Some(module) = previous.module() {
-                    // TODO query module for view eviction etc.
-                    module.evict(self, previous.clone()).await?;
-                    previous.clone().evict().await?;
+                if !query_eviction(previous).await? {
+                    return Err(Error::EvictionCancelled);
+                }
+                self.save_state()?;
+
+                // the module evicts the view in `swap_to()` unless it is
+                // kept alive in the navigation history or the cache
+                if previous.module().is_some() {
                     //previous.unsubscribe()?;
 
                     // check and abort view progress if present
@@ -113,7 +1426,6 @@ impl Container {
                     log_trace!("swap_from(): finishing...");
                     Ok(Some(previous.clone()))
                 } else {
-                    previous.clone().evict().await?;
                     //previous.unsubscribe()?;
                     Ok(None)
                 }
@@ -121,25 +1433,89 @@ impl Container {
         }
     }
 
-    /// Executes the swap, evicting the previous view and installing the new one.
-    /// Currently this is done by simply replacing children.
-    /// TODO: implement transition between views
+    /// Executes the swap using the container default transition.
     pub async fn swap_to(self: &Arc<Self>, incoming: Arc<dyn View>) -> Result<()> {
+        let (transition, _) = self.transition()?;
+        self.swap_to_with_transition(incoming, transition, Direction::Forward)
+            .await
+    }
+
+    /// Executes the swap, evicting the previous view and installing the new one.
+    /// The previous view is evicted and dropped only after its exit animation
+    /// completes. [`Direction::Back`] plays the reverse transition.
+    /// A swap started while a transition is running waits for it to
+    /// complete, so that the previous view is always attached.
+    pub async fn swap_to_with_transition(
+        self: &Arc<Self>,
+        incoming: Arc<dyn View>,
+        transition: Transition,
+        direction: Direction,
+    ) -> Result<()> {
+        let swap = self.swap_lock.lock().await;
+        let (_, duration) = self.transition()?;
+        let transition = transition.resolve(direction);
         let previous = self.view.read()?.clone();
         *self.view.write()? = Some(incoming.clone());
 
-        if let Some(previous) = previous {
-            let el = previous.element();
-            self.element.remove_child(&el)?;
-        }
-
         if let Some(app_menu) = &self.app_menu {
             //log_trace!("app_menu.update_bottom_menus: {:?}", incoming.bottom_menus());
-            app_menu.update_bottom_menus(incoming.bottom_menus())?;
+            app_menu
+                .update_bottom_menus_with_back(incoming.bottom_menus(), self.back_menu_item()?)?;
         }
 
-        self.element.append_child(&incoming.element())?;
+        if let Some(previous) = &previous {
+            self.observer.lock()?.take();
+            previous.clone().on_hide().await?;
+        }
+
+        let incoming_el = incoming.element();
+        match &previous {
+            Some(previous) => {
+                let el = previous.element();
+                if transition.is_concurrent() {
+                    self.element.append_child(&incoming_el)?;
+                    transition
+                        .play(Some(&el), Some(&incoming_el), duration)
+                        .await?;
+                    self.element.remove_child(&el)?;
+                } else {
+                    transition.exit(&el, duration).await?;
+                    self.element.remove_child(&el)?;
+                    self.element.append_child(&incoming_el)?;
+                    transition.enter(&incoming_el, duration).await?;
+                }
+            }
+            None => {
+                self.element.append_child(&incoming_el)?;
+                transition.enter(&incoming_el, duration).await?;
+            }
+        }
+        // the view hooks below may load another view
+        drop(swap);
+
         incoming.subscribe()?;
+        *self.observer.lock()? = Some(ViewObserver::try_new(&incoming)?);
+        incoming.clone().on_show().await?;
+        let restored = self.cache.lock()?.take(&incoming);
+        if restored {
+            incoming.clone().on_restore().await?;
+        }
+        crate::router::commit(self, &incoming)?;
+
+        if let Some(previous) = previous {
+            if self.history.lock()?.contains(&previous) {
+                // parked in the navigation history
+                previous.unsubscribe()?;
+            } else if self.cache.lock()?.accepts(&previous) {
+                previous.unsubscribe()?;
+                previous.clone().on_park().await?;
+                let evicted = self.cache.lock()?.park(previous);
+                self.evict_cached(evicted).await?;
+            } else {
+                self.evict_view(previous).await?;
+            }
+        }
+        self.notify_history_change()?;
 
         /*
         let mut scroll_opt = ScrollToOptions::new();
@@ -197,6 +1573,11 @@ pub trait View: Sync + Send + AnySync {
     fn element(&self) -> Element;
     fn module(&self) -> Option<Arc<dyn ModuleInterface>>;
     fn typeid(&self) -> TypeId;
+    /// Queried by [`Container::swap_from`] before the view is swapped out;
+    /// the swap is cancelled if the eviction is refused.
+    async fn eviction(self: Arc<Self>) -> Result<Eviction> {
+        Ok(Eviction::Allow)
+    }
     async fn evict(self: Arc<Self>) -> Result<()> {
         Ok(())
     }
@@ -212,10 +1593,72 @@ pub trait View: Sync + Send + AnySync {
     fn unsubscribe(&self) -> Result<()> {
         Ok(())
     }
+
+    /// Invoked once the view has been attached to its container.
+    async fn on_show(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+    /// Invoked before the view is detached from its container.
+    async fn on_hide(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+    /// Invoked when the size of the view element changes.
+    fn on_resize(&self, _width: f64, _height: f64) -> Result<()> {
+        Ok(())
+    }
+    /// Invoked when the view scrolls in or out of the viewport
+    /// or when the document visibility changes.
+    fn on_visibility(&self, _visible: bool) -> Result<()> {
+        Ok(())
+    }
+    /// Invoked when the view is restored by back/forward navigation
+    /// or becomes the top of a [`ContainerStack`] again.
+    async fn on_focus_return(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Captures the view state persisted by a [`Container`] across page
+    /// reloads; the view is rebuilt by [`ModuleInterface::restore`].
+    fn snapshot(&self) -> Result<Option<ViewState>> {
+        Ok(None)
+    }
+    /// Applies the persisted state to the view rebuilt after a page reload.
+    async fn rehydrate(self: Arc<Self>, _state: &ViewState) -> Result<()> {
+        Ok(())
+    }
+
+    /// Opt in to be parked in the [`Container`] keep-alive cache
+    /// instead of being evicted when swapped out.
+    fn keep_alive(&self) -> bool {
+        false
+    }
+    /// Distinguishes cached views of the same type.
+    fn cache_key(&self) -> Option<String> {
+        None
+    }
+    /// Invoked once the view has been parked in the keep-alive cache.
+    async fn on_park(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+    /// Invoked once the view has been restored from the keep-alive cache.
+    async fn on_restore(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
 }
 
 downcast_sync!(dyn View);
 
+/// Eviction disposition of a view (see [`View::eviction`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eviction {
+    Allow,
+    Disallow,
+    /// Ask the user to confirm discarding unsaved changes.
+    Confirm,
+}
+
+/// Queried by `#[derive(HtmlView)]` views before eviction;
+/// returning `false` cancels the view swap.
 #[workflow_async_trait]
 pub trait Evict: Sync + Send {
     async fn evict(self: Arc<Self>) -> workflow_ux::result::Result<bool> {
@@ -223,6 +1666,33 @@ pub trait Evict: Sync + Send {
     }
 }
 
+/// Lifecycle hooks of views generated by `#[view(Type, hooks)]`
+/// or `#[derive(HtmlView)]` with the `#[view_hooks]` attribute.
+#[workflow_async_trait]
+pub trait ViewHooks: Sync + Send {
+    async fn on_show(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+    async fn on_hide(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+    fn on_resize(&self, _width: f64, _height: f64) -> Result<()> {
+        Ok(())
+    }
+    fn on_visibility(&self, _visible: bool) -> Result<()> {
+        Ok(())
+    }
+    async fn on_focus_return(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+    async fn on_park(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+    async fn on_restore(self: Arc<Self>) -> Result<()> {
+        Ok(())
+    }
+}
+
 pub fn into_meta_view(view: Arc<dyn View>, meta: Arc<dyn Meta>) -> Result<Arc<dyn View>> {
     let meta_view = MetaView::try_new(view, meta)?;
     Ok(meta_view)
@@ -321,6 +1791,7 @@ pub struct Layout<F, D> {
     drop: Arc<Mutex<Option<DropFn>>>,
     element: Element,
     module: Option<Arc<dyn ModuleInterface>>,
+    dirty: Arc<Mutex<bool>>,
 }
 
 impl<F, D> Layout<F, D>
@@ -344,10 +1815,31 @@ where
             data: Arc::new(Mutex::new(data)),
             evict: Arc::new(Mutex::new(None)),
             drop: Arc::new(Mutex::new(None)),
+            dirty: Arc::new(Mutex::new(false)),
         };
         Ok(Arc::new(view))
     }
 
+    /// Follows the dirty state of the layout's [`DirtyTracker`], so that reverting
+    /// the changes no longer asks for confirmation on eviction.
+    pub fn bind_dirty_tracker(&self, tracker: &DirtyTracker) -> Result<()> {
+        *self.dirty.lock()? = tracker.is_dirty()?;
+        let dirty = self.dirty.clone();
+        tracker.on_change(Box::new(move |is_dirty| -> Result<()> {
+            *dirty.lock()? = is_dirty;
+            Ok(())
+        }))
+    }
+
+    pub fn is_dirty(&self) -> Result<bool> {
+        Ok(*self.dirty.lock()?)
+    }
+
+    pub fn set_dirty(&self, dirty: bool) -> Result<()> {
+        *self.dirty.lock()? = dirty;
+        Ok(())
+    }
+
     pub fn with_evict_handler(self: Arc<Self>, handler: EvictFn) -> Result<Arc<Self>> {
         *self.evict.lock().unwrap() = Some(handler);
         Ok(self)
@@ -392,6 +1884,14 @@ where
         TypeId::of::<Data<F>>()
     }
 
+    async fn eviction(self: Arc<Layout<F, D>>) -> Result<Eviction> {
+        if self.is_dirty()? {
+            Ok(Eviction::Confirm)
+        } else {
+            Ok(Eviction::Allow)
+        }
+    }
+
     async fn evict(self: Arc<Layout<F, D>>) -> Result<()> {
         let evict = self.evict.lock()?;
         match &*evict {
@@ -681,7 +2181,144 @@ impl View for MetaView
         TypeId::of::<Self>()
     }
 
+    async fn eviction(self: Arc<Self>) -> Result<Eviction> {
+        self.view.clone().eviction().await
+    }
+
     async fn evict(self: Arc<Self>) -> Result<()> {
         self.view.clone().evict().await
     }
+
+    async fn on_show(self: Arc<Self>) -> Result<()> {
+        self.view.clone().on_show().await
+    }
+
+    async fn on_hide(self: Arc<Self>) -> Result<()> {
+        self.view.clone().on_hide().await
+    }
+
+    fn on_resize(&self, width: f64, height: f64) -> Result<()> {
+        self.view.on_resize(width, height)
+    }
+
+    fn on_visibility(&self, visible: bool) -> Result<()> {
+        self.view.on_visibility(visible)
+    }
+
+    async fn on_focus_return(self: Arc<Self>) -> Result<()> {
+        self.view.clone().on_focus_return().await
+    }
+
+    fn snapshot(&self) -> Result<Option<ViewState>> {
+        self.view.snapshot()
+    }
+
+    async fn rehydrate(self: Arc<Self>, state: &ViewState) -> Result<()> {
+        self.view.clone().rehydrate(state).await
+    }
+
+    fn keep_alive(&self) -> bool {
+        self.view.keep_alive()
+    }
+
+    fn cache_key(&self) -> Option<String> {
+        self.view.cache_key()
+    }
+
+    async fn on_park(self: Arc<Self>) -> Result<()> {
+        self.view.clone().on_park().await
+    }
+
+    async fn on_restore(self: Arc<Self>) -> Result<()> {
+        self.view.clone().on_restore().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    struct TestView {
+        element: Element,
+        keep_alive: bool,
+        evicted: AtomicBool,
+    }
+
+    impl TestView {
+        fn new(keep_alive: bool) -> Arc<TestView> {
+            Arc::new(TestView {
+                element: document().create_element("div").unwrap(),
+                keep_alive,
+                evicted: AtomicBool::new(false),
+            })
+        }
+
+        fn evicted(&self) -> bool {
+            self.evicted.load(Ordering::SeqCst)
+        }
+    }
+
+    #[workflow_async_trait]
+    impl View for TestView {
+        fn element(&self) -> Element {
+            self.element.clone()
+        }
+        fn module(&self) -> Option<Arc<dyn ModuleInterface>> {
+            None
+        }
+        fn typeid(&self) -> TypeId {
+            TypeId::of::<Self>()
+        }
+        async fn evict(self: Arc<Self>) -> Result<()> {
+            self.evicted.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+        fn keep_alive(&self) -> bool {
+            self.keep_alive
+        }
+    }
+
+    fn container() -> Arc<Container> {
+        let element = document().create_element("div").unwrap();
+        Arc::new(Container::new(element, None))
+    }
+
+    #[wasm_bindgen_test]
+    async fn evict_views_not_kept_alive() {
+        let container = container();
+        let views = (0..4).map(|_| TestView::new(false)).collect::<Vec<_>>();
+        for view in &views {
+            container.load_view(view.clone()).await.unwrap();
+        }
+        let (last, older) = views.split_last().unwrap();
+        assert!(older.iter().all(|view| view.evicted()));
+        assert!(!last.evicted());
+        assert!(!container.can_go_back().unwrap());
+    }
+
+    #[wasm_bindgen_test]
+    async fn evict_views_exceeding_history_size() {
+        let container = container();
+        container
+            .set_cache_policy(CachePolicy::Disabled)
+            .await
+            .unwrap();
+        let views = (0..MAX_HISTORY_SIZE + 3)
+            .map(|_| TestView::new(true))
+            .collect::<Vec<_>>();
+        for view in &views {
+            container.load_view(view.clone()).await.unwrap();
+        }
+        let (oldest, kept) = views.split_at(2);
+        assert!(oldest.iter().all(|view| view.evicted()));
+        assert!(kept.iter().all(|view| !view.evicted()));
+        assert_eq!(
+            container.history.lock().unwrap().back.len(),
+            MAX_HISTORY_SIZE
+        );
+    }
 }
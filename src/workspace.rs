@@ -1,8 +1,10 @@
 use crate::app_menu::AppMenu;
 use crate::find_el;
+use crate::overlay::{self, OverlayStack};
 use crate::result::Result;
-use crate::view::{Container, ContainerStack};
+use crate::view::{Container, ContainerStack, TabContainer, TabOptions, View};
 use std::sync::Arc;
+use workflow_i18n::i18n;
 
 pub struct Workspace {
     pub header: Arc<Container>,
@@ -23,15 +25,47 @@ impl Workspace {
     ) -> Result<Workspace> {
         //let menu = Arc::new(AppMenu::new(menu_el, bottom_menu_el)?);
 
+        let main_ele = find_el(main_el, "missing workspace main element")?;
+        let main = Arc::new(Container::new(main_ele, Some(menu.clone())));
+
+        Self::try_new(header_el, status_el, main, sidebar_el, menu)
+    }
+
+    /// Creates a workspace mounting a [`TabContainer`] in the main element.
+    /// The returned main [`Container`] is hosted in the first (non-closable)
+    /// tab while modules can open additional views in their own tabs.
+    pub fn new_with_tabs(
+        header_el: &str,
+        status_el: &str,
+        main_el: &str,
+        sidebar_el: &str,
+        menu: Arc<AppMenu>,
+    ) -> Result<(Workspace, Arc<TabContainer>)> {
+        let main_ele = find_el(main_el, "missing workspace main element")?;
+        let tabs = TabContainer::try_new(main_ele)?;
+        let (_, main) = tabs.open_container_tab(
+            TabOptions::new(i18n("Home")).with_closable(false),
+            Some(menu.clone()),
+        )?;
+
+        let workspace = Self::try_new(header_el, status_el, main, sidebar_el, menu)?;
+
+        Ok((workspace, tabs))
+    }
+
+    fn try_new(
+        header_el: &str,
+        status_el: &str,
+        main: Arc<Container>,
+        sidebar_el: &str,
+        menu: Arc<AppMenu>,
+    ) -> Result<Workspace> {
         let header_ele = find_el(header_el, "missing workspace header element")?;
         let header = Arc::new(Container::new(header_ele, None));
 
         let status_ele = find_el(status_el, "missing workspace status element")?;
         let status = Arc::new(Container::new(status_ele, None));
 
-        let main_ele = find_el(main_el, "missing workspace main element")?;
-        let main = Arc::new(Container::new(main_ele, Some(menu.clone())));
-
         let sidebar_ele = find_el(sidebar_el, "missing workspace sidebar element")?;
         let sidebar = Arc::new(ContainerStack::new(sidebar_ele));
 
@@ -65,4 +99,15 @@ impl Workspace {
     pub fn sidebar(&self) -> Arc<ContainerStack> {
         self.sidebar.clone()
     }
+
+    /// Overlay stack shared by all workspaces (see [`crate::overlay::overlays`]).
+    pub fn overlays(&self) -> Result<Arc<OverlayStack>> {
+        overlay::overlays()
+    }
+
+    /// Presents the view as a modal overlay, resolving once the view
+    /// dismisses itself via [`crate::overlay::dismiss`].
+    pub async fn present<T: 'static>(&self, view: Arc<dyn View>) -> Result<T> {
+        self.overlays()?.present(view).await
+    }
 }